@@ -0,0 +1,413 @@
+//! Conversions between `MStr` collections and [arrow](https://arrow.apache.org) string arrays.
+//!
+//! The `to_*` functions pack a list of strings directly into arrow's buffers,
+//! without any intermediate per-string allocations or UTF-8 revalidation.
+//! The `iter_*` functions go the other way,
+//! yielding every value of an arrow array as a borrowed `MStr`.
+//!
+//! Requires the `arrow` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use arrow_array::{Array, StringArray};
+//!
+//! let strings = vec![MStr::new_borrowed("foo"), MStr::new_owned("bar")];
+//! let array: StringArray = mstr::arrow::to_string_array(&strings);
+//!
+//! assert_eq!(array.len(), 2);
+//! assert_eq!(array.value(1), "bar");
+//!
+//! let back: Vec<MStr<'_>> = mstr::arrow::iter_string_array(&array).flatten().collect();
+//! assert_eq!(back, strings);
+//! assert!(back.iter().all(MStr::is_borrowed));
+//! ```
+
+use crate::MStr;
+use alloc::vec::Vec;
+use arrow_array::{Array, GenericStringArray, OffsetSizeTrait, StringViewArray};
+use arrow_buffer::{Buffer, NullBufferBuilder, OffsetBuffer, ScalarBuffer};
+
+// ===== Export =====
+
+/// Packs a list of strings into an arrow `StringArray` (or `LargeStringArray`).
+///
+/// The strings can be anything that implements `AsRef<str>`, such as `MStr`, `&str`, or `String`.
+///
+/// # Panics
+///
+/// Panics if the combined length of the strings overflows the offset type
+/// (`i32` for `StringArray`, `i64` for `LargeStringArray`).
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use arrow_array::LargeStringArray;
+///
+/// let array: LargeStringArray = mstr::arrow::to_string_array(["a", "bc"]);
+///
+/// assert_eq!(array.value(0), "a");
+/// assert_eq!(array.value_offsets(), [0, 1, 3]);
+/// ```
+#[must_use]
+pub fn to_string_array<O, I>(strings: I) -> GenericStringArray<O>
+where
+    O: OffsetSizeTrait,
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    to_nullable_string_array(strings.into_iter().map(Some))
+}
+
+/// Packs a list of optional strings into an arrow `StringArray` (or `LargeStringArray`),
+/// where `None` becomes a null value.
+///
+/// # Panics
+///
+/// Panics if the combined length of the strings overflows the offset type
+/// (`i32` for `StringArray`, `i64` for `LargeStringArray`).
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use arrow_array::{Array, StringArray};
+///
+/// let array: StringArray =
+///     mstr::arrow::to_nullable_string_array([Some(MStr::new_borrowed("a")), None]);
+///
+/// assert_eq!(array.value(0), "a");
+/// assert!(array.is_null(1));
+/// ```
+#[must_use]
+pub fn to_nullable_string_array<O, I, S>(strings: I) -> GenericStringArray<O>
+where
+    O: OffsetSizeTrait,
+    I: IntoIterator<Item = Option<S>>,
+    S: AsRef<str>,
+{
+    let strings = strings.into_iter();
+    let len = strings.size_hint().0;
+
+    let mut offsets = Vec::with_capacity(len + 1);
+    let mut values = Vec::new();
+    let mut nulls = NullBufferBuilder::new(len);
+
+    offsets.push(O::usize_as(0));
+    for s in strings {
+        match s {
+            Some(s) => {
+                values.extend_from_slice(s.as_ref().as_bytes());
+                nulls.append_non_null();
+            }
+            None => nulls.append_null(),
+        }
+
+        let offset = O::from_usize(values.len()).expect("string array offset overflow");
+        offsets.push(offset);
+    }
+
+    // SAFETY: offsets start at 0, are monotonically increasing, and all lie within values.
+    // every offset is at the boundary between two complete strings, so every value is valid UTF-8.
+    // there is exactly one null entry per value.
+    unsafe {
+        let offsets = OffsetBuffer::new_unchecked(ScalarBuffer::from(offsets));
+        GenericStringArray::new_unchecked(offsets, Buffer::from_vec(values), nulls.finish())
+    }
+}
+
+/// Packs a list of strings into an arrow `StringViewArray`.
+///
+/// Strings of up to 12 bytes are stored inline in their view,
+/// and longer strings are packed into shared data buffers.
+///
+/// # Panics
+///
+/// Panics if any string is longer than `u32::MAX` bytes.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// let array = mstr::arrow::to_string_view_array(["short", "a much longer string"]);
+///
+/// assert_eq!(array.value(0), "short");
+/// assert_eq!(array.value(1), "a much longer string");
+/// assert_eq!(array.data_buffers().len(), 1);
+/// ```
+#[must_use]
+pub fn to_string_view_array<I>(strings: I) -> StringViewArray
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    to_nullable_string_view_array(strings.into_iter().map(Some))
+}
+
+/// Packs a list of optional strings into an arrow `StringViewArray`,
+/// where `None` becomes a null value.
+///
+/// See [`to_string_view_array`].
+///
+/// # Panics
+///
+/// Panics if any string is longer than `u32::MAX` bytes.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrow_array::Array;
+///
+/// let array = mstr::arrow::to_nullable_string_view_array([None, Some("foo")]);
+///
+/// assert!(array.is_null(0));
+/// assert_eq!(array.value(1), "foo");
+/// ```
+#[must_use]
+pub fn to_nullable_string_view_array<I, S>(strings: I) -> StringViewArray
+where
+    I: IntoIterator<Item = Option<S>>,
+    S: AsRef<str>,
+{
+    let strings = strings.into_iter();
+    let len = strings.size_hint().0;
+
+    let mut views = Vec::with_capacity(len);
+    let mut buffers = Vec::new();
+    let mut data = Vec::new();
+    let mut nulls = NullBufferBuilder::new(len);
+
+    for s in strings {
+        let s = match s {
+            Some(ref s) => s.as_ref().as_bytes(),
+            None => {
+                views.push(0);
+                nulls.append_null();
+                continue;
+            }
+        };
+        nulls.append_non_null();
+
+        // view layout (little-endian):
+        // len <= 12: [len: u32][data: 12 bytes, zero padded]
+        // len > 12:  [len: u32][prefix: 4 bytes][buffer index: u32][offset: u32]
+        let len = u32::try_from(s.len()).expect("string view length overflow");
+        let mut view = [0; 16];
+        view[..4].copy_from_slice(&len.to_le_bytes());
+
+        if s.len() <= 12 {
+            view[4..4 + s.len()].copy_from_slice(s);
+        } else {
+            // start a new buffer when the offset would no longer fit in a u32
+            if u32::try_from(data.len() + s.len()).is_err() {
+                buffers.push(Buffer::from_vec(core::mem::take(&mut data)));
+            }
+
+            let index = buffers.len() as u32;
+            let offset = data.len() as u32;
+            data.extend_from_slice(s);
+
+            view[4..8].copy_from_slice(&s[..4]);
+            view[8..12].copy_from_slice(&index.to_le_bytes());
+            view[12..16].copy_from_slice(&offset.to_le_bytes());
+        }
+
+        views.push(u128::from_le_bytes(view));
+    }
+
+    if !data.is_empty() {
+        buffers.push(Buffer::from_vec(data));
+    }
+
+    // SAFETY: every view was built from a complete string (so it is valid UTF-8),
+    // and points into a buffer that exists, at an offset within that buffer.
+    // there is exactly one null entry per view.
+    unsafe { StringViewArray::new_unchecked(ScalarBuffer::from(views), buffers, nulls.finish()) }
+}
+
+// ===== Import =====
+
+/// Iterates over the values of an arrow `StringArray` (or `LargeStringArray`) as borrowed `MStr`s.
+///
+/// Null values are yielded as `None`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use arrow_array::StringArray;
+///
+/// let array = StringArray::from(vec![Some("foo"), None]);
+/// let mut iter = mstr::arrow::iter_string_array(&array);
+///
+/// assert_eq!(iter.next(), Some(Some(MStr::new_borrowed("foo"))));
+/// assert_eq!(iter.next(), Some(None));
+/// assert_eq!(iter.next(), None);
+/// ```
+pub fn iter_string_array<O: OffsetSizeTrait>(
+    array: &GenericStringArray<O>,
+) -> impl DoubleEndedIterator<Item = Option<MStr<'_>>> + ExactSizeIterator {
+    array.iter().map(|s| s.map(MStr::new_borrowed))
+}
+
+/// Iterates over the values of an arrow `StringViewArray` as borrowed `MStr`s.
+///
+/// Null values are yielded as `None`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use arrow_array::StringViewArray;
+///
+/// let array = StringViewArray::from(vec![Some("foo"), None]);
+/// let values = mstr::arrow::iter_string_view_array(&array).collect::<Vec<_>>();
+///
+/// assert_eq!(values, [Some(MStr::new_borrowed("foo")), None]);
+/// ```
+pub fn iter_string_view_array(
+    array: &StringViewArray,
+) -> impl DoubleEndedIterator<Item = Option<MStr<'_>>> + ExactSizeIterator {
+    (0..array.len()).map(move |i| {
+        if array.is_null(i) {
+            None
+        } else {
+            Some(MStr::new_borrowed(array.value(i)))
+        }
+    })
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MStrVec;
+    use alloc::string::String;
+    use alloc::vec;
+    use arrow_array::{LargeStringArray, StringArray};
+
+    const STRINGS: [&str; 6] = [
+        "",
+        "short",
+        "exactly12byt",
+        "thirteen byte",
+        "🐸 a longer string with non-ascii 🐸",
+        "x",
+    ];
+
+    #[test]
+    fn string_array() {
+        let array: StringArray = to_string_array(STRINGS);
+
+        // passes arrow's own validation
+        array.to_data().validate_full().unwrap();
+
+        assert_eq!(array.len(), STRINGS.len());
+        assert_eq!(array.null_count(), 0);
+        assert!(array.iter().map(Option::unwrap).eq(STRINGS));
+    }
+
+    #[test]
+    fn large_string_array() {
+        let array: LargeStringArray = to_string_array(STRINGS.map(MStr::new_owned));
+        array.to_data().validate_full().unwrap();
+
+        assert!(array.iter().map(Option::unwrap).eq(STRINGS));
+    }
+
+    #[test]
+    fn nullable_string_array() {
+        let array: StringArray = to_nullable_string_array(vec![
+            Some(String::from("a")),
+            None,
+            Some(String::from("bc")),
+            None,
+        ]);
+        array.to_data().validate_full().unwrap();
+
+        assert_eq!(array.null_count(), 2);
+        assert_eq!(
+            array.iter().collect::<Vec<_>>(),
+            [Some("a"), None, Some("bc"), None]
+        );
+        assert_eq!(array.value_offsets(), [0, 1, 1, 3, 3]);
+    }
+
+    #[test]
+    fn empty() {
+        let array: StringArray = to_string_array(Vec::<MStr<'_>>::new());
+        array.to_data().validate_full().unwrap();
+        assert!(array.is_empty());
+
+        let array = to_string_view_array(Vec::<MStr<'_>>::new());
+        array.to_data().validate_full().unwrap();
+        assert!(array.is_empty());
+    }
+
+    #[test]
+    fn string_view_array() {
+        let array = to_string_view_array(STRINGS);
+        array.to_data().validate_full().unwrap();
+
+        assert_eq!(array.len(), STRINGS.len());
+        assert!(array.iter().map(Option::unwrap).eq(STRINGS));
+
+        // only the long strings end up in a data buffer
+        assert_eq!(array.data_buffers().len(), 1);
+        assert_eq!(
+            array.data_buffers()[0].len(),
+            STRINGS[3].len() + STRINGS[4].len()
+        );
+    }
+
+    #[test]
+    fn nullable_string_view_array() {
+        let array = to_nullable_string_view_array([None, Some(STRINGS[4]), None, Some("a")]);
+        array.to_data().validate_full().unwrap();
+
+        assert_eq!(
+            array.iter().collect::<Vec<_>>(),
+            [None, Some(STRINGS[4]), None, Some("a")]
+        );
+    }
+
+    #[test]
+    fn from_mstr_vec() {
+        let vec = MStrVec::from_iter(STRINGS);
+        let array: StringArray = to_string_array(&vec);
+
+        assert!(array.iter().map(Option::unwrap).eq(vec.iter()));
+    }
+
+    #[test]
+    fn iter_borrowed() {
+        let array: StringArray = to_nullable_string_array([Some("a"), None, Some("b")]);
+        let values = iter_string_array(&array).collect::<Vec<_>>();
+
+        assert_eq!(values, [Some(MStr::from("a")), None, Some(MStr::from("b"))]);
+        assert!(values.iter().flatten().all(MStr::is_borrowed));
+        assert_eq!(
+            values[2].as_ref().unwrap().as_ptr(),
+            array.value(2).as_ptr()
+        );
+
+        let rev = iter_string_array(&array).rev().collect::<Vec<_>>();
+        assert_eq!(rev, [Some(MStr::from("b")), None, Some(MStr::from("a"))]);
+    }
+
+    #[test]
+    fn iter_view_borrowed() {
+        let array = to_nullable_string_view_array([Some(STRINGS[3]), None, Some("a")]);
+        let values = iter_string_view_array(&array).collect::<Vec<_>>();
+
+        assert_eq!(
+            values,
+            [Some(MStr::from(STRINGS[3])), None, Some(MStr::from("a"))]
+        );
+        assert!(values.iter().flatten().all(MStr::is_borrowed));
+        assert_eq!(iter_string_view_array(&array).len(), 3);
+    }
+}
@@ -0,0 +1,637 @@
+use crate::MStr;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::fmt::{Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::ops::{
+    Deref, Index, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+};
+
+/// An [`MStr`] that is known to only contain ASCII.
+///
+/// Because every byte is a whole character,
+/// indexing by character is O(1) ([`char_at`](AsciiMStr::char_at)),
+/// and any byte range is a valid slice.
+///
+/// Case conversions only copy the string if it actually changes,
+/// so borrowed strings that are already in the target case stay borrowed.
+///
+/// `AsciiMStr` is the same size as `MStr` (2 words).
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::AsciiMStr;
+/// let s = AsciiMStr::new("Content-Type").unwrap();
+/// assert_eq!(s.char_at(8), Some('T'));
+/// assert_eq!(s[0], b'C');
+///
+/// let lower = s.into_ascii_lowercase();
+/// assert_eq!(lower, "content-type");
+/// assert!(lower.is_owned());
+///
+/// let already = AsciiMStr::new("accept").unwrap().into_ascii_lowercase();
+/// assert!(already.is_borrowed());
+///
+/// assert!(AsciiMStr::new("naïve").is_err());
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct AsciiMStr<'a>(MStr<'a>);
+
+impl<'a> AsciiMStr<'a> {
+    // -- Constructors --
+
+    /// Creates a new `AsciiMStr`, checking that `s` is ASCII.
+    ///
+    /// On failure, the string is returned inside the error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::AsciiMStr;
+    /// assert!(AsciiMStr::new("frog").is_ok());
+    ///
+    /// let err = AsciiMStr::new(String::from("fr🐸g")).unwrap_err();
+    /// assert_eq!(err.valid_up_to(), 2);
+    /// assert_eq!(err.into_mstr(), "fr🐸g");
+    /// ```
+    #[inline]
+    pub fn new(s: impl Into<MStr<'a>>) -> Result<AsciiMStr<'a>, NotAsciiError<'a>> {
+        let s = s.into();
+        match s.bytes().position(|b| !b.is_ascii()) {
+            None => Ok(AsciiMStr(s)),
+            Some(valid_up_to) => Err(NotAsciiError { s, valid_up_to }),
+        }
+    }
+
+    // -- Accessors --
+
+    /// Converts this `AsciiMStr` to a string slice.
+    #[inline]
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Converts this `AsciiMStr` to a byte slice.
+    #[inline]
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    /// Gets a reference to the underlying `MStr`.
+    #[inline]
+    #[must_use]
+    pub const fn as_mstr(&self) -> &MStr<'a> {
+        &self.0
+    }
+
+    /// Gets the character at index `i`, or `None` if it is out of bounds.
+    ///
+    /// Unlike `str::chars().nth(i)`, this is O(1).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::AsciiMStr;
+    /// let s = AsciiMStr::new("abc").unwrap();
+    /// assert_eq!(s.char_at(1), Some('b'));
+    /// assert_eq!(s.char_at(3), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn char_at(&self, i: usize) -> Option<char> {
+        self.as_bytes().get(i).map(|&b| b as char)
+    }
+
+    /// Gets the length of the underlying string,
+    /// which is both the number of bytes and the number of characters.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Checks if the underlying string is empty (length of 0).
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Checks if the underlying string is owned.
+    #[inline]
+    #[must_use]
+    pub const fn is_owned(&self) -> bool {
+        self.0.is_owned()
+    }
+
+    /// Checks if the underlying string is borrowed.
+    #[inline]
+    #[must_use]
+    pub const fn is_borrowed(&self) -> bool {
+        self.0.is_borrowed()
+    }
+
+    // -- Case --
+
+    /// Converts this string to ASCII lowercase.
+    ///
+    /// If there are no uppercase letters, `self` is returned unchanged (and stays borrowed, if it was).
+    /// Otherwise owned strings are converted in place, and borrowed strings are copied.
+    #[must_use]
+    pub fn into_ascii_lowercase(self) -> AsciiMStr<'a> {
+        if !self.bytes().any(|b| b.is_ascii_uppercase()) {
+            return self;
+        }
+
+        let mut s = self.0.into_boxed();
+        s.make_ascii_lowercase();
+        AsciiMStr(MStr::new_owned(s))
+    }
+
+    /// Converts this string to ASCII uppercase.
+    ///
+    /// If there are no lowercase letters, `self` is returned unchanged (and stays borrowed, if it was).
+    /// Otherwise owned strings are converted in place, and borrowed strings are copied.
+    #[must_use]
+    pub fn into_ascii_uppercase(self) -> AsciiMStr<'a> {
+        if !self.bytes().any(|b| b.is_ascii_lowercase()) {
+            return self;
+        }
+
+        let mut s = self.0.into_boxed();
+        s.make_ascii_uppercase();
+        AsciiMStr(MStr::new_owned(s))
+    }
+
+    /// Compares two strings, ignoring ASCII case.
+    ///
+    /// This is consistent with [`eq_ignore_ascii_case`](str::eq_ignore_ascii_case)
+    /// and [`hash_ignore_ascii_case`](AsciiMStr::hash_ignore_ascii_case).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::AsciiMStr;
+    /// # use std::cmp::Ordering;
+    /// let s = AsciiMStr::new("Apple").unwrap();
+    /// assert_eq!(s.cmp_ignore_ascii_case("apple"), Ordering::Equal);
+    /// assert_eq!(s.cmp_ignore_ascii_case("banana"), Ordering::Less);
+    /// ```
+    #[must_use]
+    pub fn cmp_ignore_ascii_case(&self, other: &str) -> Ordering {
+        let a = self.bytes().map(|b| b.to_ascii_lowercase());
+        let b = other.bytes().map(|b| b.to_ascii_lowercase());
+        a.cmp(b)
+    }
+
+    /// Feeds this string into `state`, ignoring ASCII case.
+    ///
+    /// Strings that are [`eq_ignore_ascii_case`](str::eq_ignore_ascii_case) hash the same.
+    pub fn hash_ignore_ascii_case<H: Hasher>(&self, state: &mut H) {
+        for b in self.bytes() {
+            state.write_u8(b.to_ascii_lowercase());
+        }
+        // same terminator as str's Hash impl, so "ab" + "c" != "a" + "bc"
+        state.write_u8(0xff);
+    }
+
+    // -- Conversions --
+
+    /// Converts this `AsciiMStr` into the underlying `MStr`.
+    #[inline]
+    #[must_use]
+    pub fn into_mstr(self) -> MStr<'a> {
+        self.0
+    }
+
+    /// Converts this `AsciiMStr` into an owned `String`.
+    ///
+    /// This will avoid allocating if `self` is owned.
+    #[inline]
+    #[must_use]
+    pub fn into_string(self) -> String {
+        self.0.into_string()
+    }
+}
+
+// ===== Error =====
+
+/// The error returned when creating an [`AsciiMStr`] from a string that isn't ASCII.
+///
+/// The string can be recovered with [`into_mstr`](NotAsciiError::into_mstr).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotAsciiError<'a> {
+    s: MStr<'a>,
+    valid_up_to: usize,
+}
+
+impl<'a> NotAsciiError<'a> {
+    /// Returns the index of the first non-ASCII byte.
+    #[inline]
+    #[must_use]
+    pub const fn valid_up_to(&self) -> usize {
+        self.valid_up_to
+    }
+
+    /// Gets the string that failed to convert.
+    #[inline]
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        self.s.as_str()
+    }
+
+    /// Returns the string that failed to convert.
+    #[inline]
+    #[must_use]
+    pub fn into_mstr(self) -> MStr<'a> {
+        self.s
+    }
+}
+
+impl Display for NotAsciiError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "non-ASCII byte at index {}", self.valid_up_to)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NotAsciiError<'_> {}
+
+// ===== Trait Impls =====
+
+// -- Format --
+
+impl Debug for AsciiMStr<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Display for AsciiMStr<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+// -- Convert From --
+
+impl<'a> TryFrom<MStr<'a>> for AsciiMStr<'a> {
+    type Error = NotAsciiError<'a>;
+
+    #[inline]
+    fn try_from(s: MStr<'a>) -> Result<Self, Self::Error> {
+        AsciiMStr::new(s)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for AsciiMStr<'a> {
+    type Error = NotAsciiError<'a>;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        AsciiMStr::new(s)
+    }
+}
+
+impl<'a> TryFrom<Cow<'a, str>> for AsciiMStr<'a> {
+    type Error = NotAsciiError<'a>;
+
+    #[inline]
+    fn try_from(s: Cow<'a, str>) -> Result<Self, Self::Error> {
+        AsciiMStr::new(s)
+    }
+}
+
+impl TryFrom<String> for AsciiMStr<'_> {
+    type Error = NotAsciiError<'static>;
+
+    #[inline]
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        AsciiMStr::new(s).map_err(|e| NotAsciiError {
+            s: MStr::new_owned(e.s.into_boxed()),
+            valid_up_to: e.valid_up_to,
+        })
+    }
+}
+
+impl TryFrom<Box<str>> for AsciiMStr<'_> {
+    type Error = NotAsciiError<'static>;
+
+    #[inline]
+    fn try_from(s: Box<str>) -> Result<Self, Self::Error> {
+        AsciiMStr::try_from(String::from(s))
+    }
+}
+
+// -- Convert To --
+
+impl<'a> From<AsciiMStr<'a>> for MStr<'a> {
+    #[inline]
+    fn from(s: AsciiMStr<'a>) -> Self {
+        s.0
+    }
+}
+
+impl From<AsciiMStr<'_>> for String {
+    #[inline]
+    fn from(s: AsciiMStr<'_>) -> Self {
+        s.into_string()
+    }
+}
+
+// -- Convert Ref --
+
+impl Deref for AsciiMStr<'_> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for AsciiMStr<'_> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for AsciiMStr<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Borrow<str> for AsciiMStr<'_> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Index<usize> for AsciiMStr<'_> {
+    type Output = u8;
+
+    #[inline]
+    fn index(&self, i: usize) -> &u8 {
+        &self.as_bytes()[i]
+    }
+}
+
+// implementing Index<usize> stops indexing from going through Deref,
+// so the range impls have to be forwarded to str by hand
+macro_rules! impl_index_range {
+    ($($range:ty),*) => {
+        $(
+            impl Index<$range> for AsciiMStr<'_> {
+                type Output = str;
+
+                #[inline]
+                fn index(&self, range: $range) -> &str {
+                    &self.as_str()[range]
+                }
+            }
+        )*
+    };
+}
+
+impl_index_range!(
+    Range<usize>,
+    RangeFrom<usize>,
+    RangeFull,
+    RangeInclusive<usize>,
+    RangeTo<usize>,
+    RangeToInclusive<usize>
+);
+
+// -- PartialEq --
+
+impl PartialEq<str> for AsciiMStr<'_> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<AsciiMStr<'_>> for str {
+    #[inline]
+    fn eq(&self, other: &AsciiMStr<'_>) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<&str> for AsciiMStr<'_> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<AsciiMStr<'_>> for &str {
+    #[inline]
+    fn eq(&self, other: &AsciiMStr<'_>) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<MStr<'_>> for AsciiMStr<'_> {
+    #[inline]
+    fn eq(&self, other: &MStr<'_>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<AsciiMStr<'_>> for MStr<'_> {
+    #[inline]
+    fn eq(&self, other: &AsciiMStr<'_>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<String> for AsciiMStr<'_> {
+    #[inline]
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<AsciiMStr<'_>> for String {
+    #[inline]
+    fn eq(&self, other: &AsciiMStr<'_>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+// ===== serde =====
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::*;
+    use serde::de::{Deserialize, Deserializer, Error};
+    use serde::ser::{Serialize, Serializer};
+
+    impl Serialize for AsciiMStr<'_> {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_str(self.as_str())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for AsciiMStr<'_> {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            let s = MStr::deserialize(d)?;
+            AsciiMStr::new(s).map_err(D::Error::custom)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+        #[test]
+        fn basic() {
+            assert_tokens(&AsciiMStr::new("roar").unwrap(), &[Token::Str("roar")]);
+            assert_de_tokens_error::<AsciiMStr<'_>>(
+                &[Token::Str("r🐸ar")],
+                "non-ASCII byte at index 1",
+            );
+        }
+    }
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem;
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash_ignore_case(s: &AsciiMStr<'_>) -> u64 {
+        let mut h = DefaultHasher::new();
+        s.hash_ignore_ascii_case(&mut h);
+        h.finish()
+    }
+
+    #[test]
+    fn size() {
+        assert_eq!(mem::size_of::<AsciiMStr<'_>>(), mem::size_of::<MStr<'_>>());
+        assert_eq!(
+            mem::size_of::<Option<AsciiMStr<'_>>>(),
+            mem::size_of::<MStr<'_>>()
+        );
+    }
+
+    #[test]
+    fn new() {
+        let s = AsciiMStr::new("hello").unwrap();
+        assert_eq!(s, "hello");
+        assert!(s.is_borrowed());
+
+        assert!(AsciiMStr::new(String::from("hello")).unwrap().is_owned());
+        assert!(AsciiMStr::new("").unwrap().is_empty());
+
+        let err = AsciiMStr::new("abc\u{80}").unwrap_err();
+        assert_eq!(err.valid_up_to(), 3);
+        assert_eq!(err.as_str(), "abc\u{80}");
+        assert!(err.into_mstr().is_borrowed());
+    }
+
+    #[test]
+    fn try_from() {
+        assert!(AsciiMStr::try_from("a").is_ok());
+        assert!(AsciiMStr::try_from(MStr::new_owned("a")).is_ok());
+        assert!(AsciiMStr::try_from(Cow::Borrowed("a")).is_ok());
+        assert!(AsciiMStr::try_from(Box::<str>::from("a")).is_ok());
+
+        let err: NotAsciiError<'static> = AsciiMStr::try_from(String::from("é")).unwrap_err();
+        assert_eq!(err.valid_up_to(), 0);
+        assert_eq!(err.into_mstr(), "é");
+    }
+
+    #[test]
+    fn indexing() {
+        let s = AsciiMStr::new("abc").unwrap();
+
+        assert_eq!(s.char_at(0), Some('a'));
+        assert_eq!(s.char_at(2), Some('c'));
+        assert_eq!(s.char_at(3), None);
+        assert_eq!(s[1], b'b');
+        assert_eq!(&s[1..], "bc");
+        assert_eq!(&s[..=1], "ab");
+        assert_eq!(&s[..], "abc");
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let _ = AsciiMStr::new("abc").unwrap()[3];
+    }
+
+    #[test]
+    fn case_conversion() {
+        let lower = AsciiMStr::new("abc-1").unwrap().into_ascii_lowercase();
+        assert_eq!(lower, "abc-1");
+        assert!(lower.is_borrowed());
+
+        let lower = AsciiMStr::new("aBc-1").unwrap().into_ascii_lowercase();
+        assert_eq!(lower, "abc-1");
+        assert!(lower.is_owned());
+
+        let upper = AsciiMStr::new("ABC-1").unwrap().into_ascii_uppercase();
+        assert!(upper.is_borrowed());
+
+        let owned = AsciiMStr::new(String::from("aBc")).unwrap();
+        let ptr = owned.as_ptr();
+        let upper = owned.into_ascii_uppercase();
+        assert_eq!(upper, "ABC");
+        assert_eq!(upper.as_ptr(), ptr); // converted in place
+    }
+
+    #[test]
+    fn ignore_case() {
+        let a = AsciiMStr::new("Content-Length").unwrap();
+        let b = AsciiMStr::new("content-length").unwrap();
+        let c = AsciiMStr::new("content-type").unwrap();
+
+        assert!(a.eq_ignore_ascii_case(&b));
+        assert_eq!(a.cmp_ignore_ascii_case(&b), Ordering::Equal);
+        assert_eq!(a.cmp_ignore_ascii_case(&c), Ordering::Less);
+        assert_eq!(a.cmp_ignore_ascii_case("content"), Ordering::Greater);
+
+        assert_eq!(hash_ignore_case(&a), hash_ignore_case(&b));
+        assert_ne!(hash_ignore_case(&a), hash_ignore_case(&c));
+    }
+
+    #[test]
+    fn traits() {
+        let s = AsciiMStr::new("abc").unwrap();
+
+        assert_eq!(alloc::format!("{s:?}"), "\"abc\"");
+        assert_eq!(alloc::format!("{s}"), "abc");
+        assert_eq!(s, MStr::from("abc"));
+        assert_eq!(MStr::from(s.clone()), "abc");
+        assert_eq!(String::from(s.clone()), "abc");
+        assert_eq!("abc", s);
+        assert!(s < AsciiMStr::new("abd").unwrap());
+        assert_eq!(AsciiMStr::default(), "");
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<AsciiMStr<'_>>();
+        assert_send_sync::<NotAsciiError<'_>>();
+    }
+}
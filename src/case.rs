@@ -0,0 +1,467 @@
+//! Converting identifiers between `snake_case`, `camelCase`, `kebab-case`, `PascalCase` and `SHOUTY_SNAKE_CASE`.
+//!
+//! Every function here takes an `MStr<'a>` and returns an `MStr<'a>`.
+//! If the input is already in the target case, it is returned untouched,
+//! so borrowed strings stay borrowed and owned strings keep their allocation.
+//!
+//! # Word boundaries
+//!
+//! The input is split into words, which are then joined in the target case.
+//! A word boundary is:
+//!
+//! - any character that isn't a letter or digit (like `_`, `-` or a space), which is dropped,
+//! - a lowercase letter followed by an uppercase one (`foo|Bar`),
+//! - the last uppercase letter in a run, if it is followed by a lowercase one (`HTTP|Server`).
+//!
+//! Digits (and letters without case, like CJK) belong to the word before them (`utf8|Bytes`, `version2`).
+//! An uppercase letter after a word with no cased letters (only digits, say) starts a new word (`9|Lives`),
+//! unless it is followed by another uppercase letter (`2FA`).
+//! Leading, trailing and repeated separators are dropped.
+//!
+//! In `camelCase` and `PascalCase`, two one-letter words in a row run together into what reads as an acronym
+//! (`get_a_b` becomes `getAB`). That output doesn't split back into the same words (`getAB` becomes `get_ab`),
+//! and isn't left unchanged by converting it again (`XY` becomes `Xy`).
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use mstr::case;
+//!
+//! let s = case::to_snake_case(MStr::new_borrowed("parseHTTPResponse"));
+//! assert_eq!(s, "parse_http_response");
+//!
+//! // already snake_case, so nothing is allocated
+//! let s = case::to_snake_case(MStr::new_borrowed("parse_http_response"));
+//! assert!(s.is_borrowed());
+//! ```
+
+use crate::MStr;
+use alloc::string::String;
+
+/// Converts `s` to `snake_case`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::case;
+///
+/// assert_eq!(case::to_snake_case(MStr::new_borrowed("XMLHttpRequest")), "xml_http_request");
+/// ```
+#[must_use]
+pub fn to_snake_case(s: MStr<'_>) -> MStr<'_> {
+    convert(s, Style::Lower('_'))
+}
+
+/// Converts `s` to `kebab-case`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::case;
+///
+/// assert_eq!(case::to_kebab_case(MStr::new_borrowed("XMLHttpRequest")), "xml-http-request");
+/// ```
+#[must_use]
+pub fn to_kebab_case(s: MStr<'_>) -> MStr<'_> {
+    convert(s, Style::Lower('-'))
+}
+
+/// Converts `s` to `SHOUTY_SNAKE_CASE`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::case;
+///
+/// assert_eq!(case::to_shouty_snake_case(MStr::new_borrowed("XMLHttpRequest")), "XML_HTTP_REQUEST");
+/// ```
+#[must_use]
+pub fn to_shouty_snake_case(s: MStr<'_>) -> MStr<'_> {
+    convert(s, Style::Upper('_'))
+}
+
+/// Converts `s` to `camelCase`.
+///
+/// The first word is lowercased, and the rest are capitalized,
+/// so acronyms are only capitalized at the start (`xmlHttpRequest`).
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::case;
+///
+/// assert_eq!(case::to_camel_case(MStr::new_borrowed("XMLHttpRequest")), "xmlHttpRequest");
+/// ```
+#[must_use]
+pub fn to_camel_case(s: MStr<'_>) -> MStr<'_> {
+    convert(s, Style::Camel)
+}
+
+/// Converts `s` to `PascalCase`.
+///
+/// Every word is capitalized, so acronyms are only capitalized at the start (`XmlHttpRequest`).
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::case;
+///
+/// assert_eq!(case::to_pascal_case(MStr::new_borrowed("xml_http_request")), "XmlHttpRequest");
+/// ```
+#[must_use]
+pub fn to_pascal_case(s: MStr<'_>) -> MStr<'_> {
+    convert(s, Style::Pascal)
+}
+
+#[derive(Clone, Copy)]
+enum Style {
+    // every word lowercased, with a separator
+    Lower(char),
+    // every word uppercased, with a separator
+    Upper(char),
+    Camel,
+    Pascal,
+}
+
+fn convert(s: MStr<'_>, style: Style) -> MStr<'_> {
+    // check for conforming input first, without allocating
+    let mut rest = s.chars();
+    let mut same = true;
+    write_case(&s, style, |c| same &= rest.next() == Some(c));
+    if same && rest.next().is_none() {
+        return s;
+    }
+
+    let mut out = String::with_capacity(s.len() + s.len() / 4);
+    write_case(&s, style, |c| out.push(c));
+    MStr::new_owned(out)
+}
+
+// writes `s` in the given style, one char at a time
+fn write_case(s: &str, style: Style, mut push: impl FnMut(char)) {
+    let mut first = true;
+
+    for_each_word(s, |word| {
+        match style {
+            Style::Lower(sep) | Style::Upper(sep) if !first => push(sep),
+            _ => {}
+        }
+
+        match style {
+            Style::Lower(_) => word
+                .chars()
+                .flat_map(char::to_lowercase)
+                .for_each(&mut push),
+            Style::Upper(_) => word
+                .chars()
+                .flat_map(char::to_uppercase)
+                .for_each(&mut push),
+            Style::Camel if first => word
+                .chars()
+                .flat_map(char::to_lowercase)
+                .for_each(&mut push),
+            Style::Camel | Style::Pascal => {
+                let mut chars = word.chars();
+                // only the first char of the mapping stays uppercase (`ß` becomes `Ss`, not `SS`),
+                // so the result splits back into the same word
+                let mut upper = chars.next().into_iter().flat_map(char::to_uppercase);
+                upper.next().into_iter().for_each(&mut push);
+                upper.flat_map(char::to_lowercase).for_each(&mut push);
+                chars.flat_map(char::to_lowercase).for_each(&mut push);
+            }
+        }
+
+        first = false;
+    });
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Boundary,
+    Lowercase,
+    Uppercase,
+}
+
+// calls `f` with every word in `s` (see the module docs for the rules)
+fn for_each_word<'s>(s: &'s str, mut f: impl FnMut(&'s str)) {
+    // the start of the current word, if in one
+    let mut start = None;
+    let mut mode = Mode::Boundary;
+
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if !c.is_alphanumeric() {
+            if let Some(start) = start.take() {
+                f(&s[start..i]);
+            }
+            mode = Mode::Boundary;
+            continue;
+        }
+
+        // 9Lives, but not 2FA (the mode is still `Boundary` if the word has no cased letters yet)
+        if mode == Mode::Boundary
+            && c.is_uppercase()
+            && !chars.peek().map_or(false, |&(_, next)| next.is_uppercase())
+        {
+            if let Some(start) = start.take() {
+                f(&s[start..i]);
+            }
+        }
+
+        let word_start = *start.get_or_insert(i);
+        // digits (and uncased letters) don't change the mode
+        let next_mode = if c.is_lowercase() {
+            Mode::Lowercase
+        } else if c.is_uppercase() {
+            Mode::Uppercase
+        } else {
+            mode
+        };
+
+        match chars.peek() {
+            // fooBar
+            Some(&(j, next)) if next_mode == Mode::Lowercase && next.is_uppercase() => {
+                f(&s[word_start..j]);
+                start = None;
+                mode = Mode::Boundary;
+            }
+            // HTTPServer
+            Some(&(_, next))
+                if mode == Mode::Uppercase && c.is_uppercase() && next.is_lowercase() =>
+            {
+                if word_start < i {
+                    f(&s[word_start..i]);
+                }
+                start = Some(i);
+                mode = next_mode;
+            }
+            _ => mode = next_mode,
+        }
+    }
+
+    if let Some(start) = start {
+        f(&s[start..]);
+    }
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    type Case = fn(MStr<'_>) -> MStr<'_>;
+
+    fn words(s: &str) -> Vec<&str> {
+        let mut words = Vec::new();
+        for_each_word(s, |w| words.push(w));
+        words
+    }
+
+    #[test]
+    fn word_boundaries() {
+        let empty: [&str; 0] = [];
+        assert_eq!(words(""), empty);
+        assert_eq!(words("_-_ "), empty);
+        assert_eq!(words("foo"), ["foo"]);
+        assert_eq!(words("FOO"), ["FOO"]);
+        assert_eq!(words("fooBar"), ["foo", "Bar"]);
+        assert_eq!(words("FooBar"), ["Foo", "Bar"]);
+        assert_eq!(
+            words("foo_bar-baz qux.quux"),
+            ["foo", "bar", "baz", "qux", "quux"]
+        );
+        assert_eq!(words("__foo__bar__"), ["foo", "bar"]);
+        assert_eq!(words("HTTPServer"), ["HTTP", "Server"]);
+        assert_eq!(words("getHTTPResponse"), ["get", "HTTP", "Response"]);
+        assert_eq!(words("ABc"), ["A", "Bc"]);
+        assert_eq!(words("aB"), ["a", "B"]);
+        assert_eq!(words("AB"), ["AB"]);
+        assert_eq!(words("Ab"), ["Ab"]);
+        assert_eq!(words("a"), ["a"]);
+        assert_eq!(words("A"), ["A"]);
+    }
+
+    #[test]
+    fn word_boundaries_digits() {
+        assert_eq!(words("utf8Bytes"), ["utf8", "Bytes"]);
+        assert_eq!(words("UTF8Bytes"), ["UTF8", "Bytes"]);
+        assert_eq!(words("version2"), ["version2"]);
+        assert_eq!(words("v2_0"), ["v2", "0"]);
+        assert_eq!(words("Base64url"), ["Base64url"]);
+        assert_eq!(words("sha256Sum"), ["sha256", "Sum"]);
+        assert_eq!(words("X509Cert"), ["X509", "Cert"]);
+        assert_eq!(words("1stPlace"), ["1st", "Place"]);
+        assert_eq!(words("9Lives"), ["9", "Lives"]);
+        assert_eq!(words("9X"), ["9", "X"]);
+        assert_eq!(words("9X_Y"), ["9", "X", "Y"]);
+        assert_eq!(words("2FA"), ["2FA"]);
+        assert_eq!(words("2FAToken"), ["2FA", "Token"]);
+        assert_eq!(words("v2Api"), ["v2", "Api"]);
+        assert_eq!(words("123"), ["123"]);
+    }
+
+    #[test]
+    fn word_boundaries_unicode() {
+        assert_eq!(words("élanVital"), ["élan", "Vital"]);
+        assert_eq!(words("ÉLANVital"), ["ÉLAN", "Vital"]);
+        assert_eq!(words("straße_öl"), ["straße", "öl"]);
+        // uncased letters are like digits
+        assert_eq!(words("日本語Text"), ["日本語", "Text"]);
+        assert_eq!(words("text日本語Text"), ["text日本語", "Text"]);
+        assert_eq!(words("get日本語"), ["get日本語"]);
+        assert_eq!(words("🐸frog🐸Toad"), ["frog", "Toad"]);
+    }
+
+    #[test]
+    fn matrix() {
+        let cases: [(&str, Case); 5] = [
+            ("snake", to_snake_case),
+            ("kebab", to_kebab_case),
+            ("shouty", to_shouty_snake_case),
+            ("camel", to_camel_case),
+            ("pascal", to_pascal_case),
+        ];
+
+        #[rustfmt::skip]
+        let matrix: &[(&str, [&str; 5])] = &[
+            // input                  snake                    kebab                    shouty                   camel                  pascal
+            ("",                      ["",                     "",                      "",                      "",                    ""]),
+            ("foo",                   ["foo",                  "foo",                   "FOO",                   "foo",                 "Foo"]),
+            ("fooBar",                ["foo_bar",              "foo-bar",               "FOO_BAR",               "fooBar",              "FooBar"]),
+            ("FooBar",                ["foo_bar",              "foo-bar",               "FOO_BAR",               "fooBar",              "FooBar"]),
+            ("foo_bar",               ["foo_bar",              "foo-bar",               "FOO_BAR",               "fooBar",              "FooBar"]),
+            ("foo-bar",               ["foo_bar",              "foo-bar",               "FOO_BAR",               "fooBar",              "FooBar"]),
+            ("FOO_BAR",               ["foo_bar",              "foo-bar",               "FOO_BAR",               "fooBar",              "FooBar"]),
+            ("Foo Bar",               ["foo_bar",              "foo-bar",               "FOO_BAR",               "fooBar",              "FooBar"]),
+            ("__foo__bar__",          ["foo_bar",              "foo-bar",               "FOO_BAR",               "fooBar",              "FooBar"]),
+            ("HTTPServer",            ["http_server",          "http-server",           "HTTP_SERVER",           "httpServer",          "HttpServer"]),
+            ("getHTTPResponseCode",   ["get_http_response_code", "get-http-response-code", "GET_HTTP_RESPONSE_CODE", "getHttpResponseCode", "GetHttpResponseCode"]),
+            ("XMLHttpRequest",        ["xml_http_request",     "xml-http-request",      "XML_HTTP_REQUEST",      "xmlHttpRequest",      "XmlHttpRequest"]),
+            ("utf8Bytes",             ["utf8_bytes",           "utf8-bytes",            "UTF8_BYTES",            "utf8Bytes",           "Utf8Bytes"]),
+            ("v2_0",                  ["v2_0",                 "v2-0",                  "V2_0",                  "v20",                 "V20"]),
+            ("ABc",                   ["a_bc",                 "a-bc",                  "A_BC",                  "aBc",                 "ABc"]),
+            ("élanVital",             ["élan_vital",           "élan-vital",            "ÉLAN_VITAL",            "élanVital",           "ÉlanVital"]),
+            ("straße",                ["straße",               "straße",                "STRASSE",               "straße",              "Straße"]),
+            ("9_lives",               ["9_lives",              "9-lives",               "9_LIVES",               "9Lives",              "9Lives"]),
+            ("9Lives",                ["9_lives",              "9-lives",               "9_LIVES",               "9Lives",              "9Lives"]),
+            ("x_ß",                   ["x_ß",                  "x-ß",                   "X_SS",                  "xSs",                 "XSs"]),
+            ("x_y",                   ["x_y",                  "x-y",                   "X_Y",                   "xY",                  "XY"]),
+            ("a_b_c",                 ["a_b_c",                "a-b-c",                 "A_B_C",                 "aBC",                 "ABC"]),
+            ("get_a_b",               ["get_a_b",              "get-a-b",               "GET_A_B",               "getAB",               "GetAB"]),
+        ];
+
+        for (input, expected) in matrix {
+            for ((name, case), expected) in cases.iter().zip(expected) {
+                let out = case(MStr::new_borrowed(input));
+                assert_eq!(out, *expected, "{name} case of {input:?}");
+                // borrowed exactly when nothing changed
+                assert_eq!(
+                    out.is_borrowed(),
+                    input == expected,
+                    "{name} case of {input:?}"
+                );
+            }
+        }
+    }
+
+    // each case, and whether it joins words without a separator
+    const CASES: [(Case, bool); 5] = [
+        (to_snake_case, false),
+        (to_kebab_case, false),
+        (to_shouty_snake_case, false),
+        (to_camel_case, true),
+        (to_pascal_case, true),
+    ];
+
+    // joining these words runs one-letter words together (see the module docs)
+    fn runs_together(s: &str) -> bool {
+        let one_letter = |w: &&str| w.chars().count() == 1 && w.chars().all(char::is_alphabetic);
+        words(s).windows(2).any(|w| w.iter().all(one_letter))
+    }
+
+    #[test]
+    fn idempotent() {
+        let inputs = [
+            "fooBarBaz",
+            "HTTP_SERVER",
+            "x509-cert",
+            "Σίσυφος καλός",
+            "9_lives",
+            "2fa_token",
+            "9_x",
+            "x_ß",
+            "ẞ_straße",
+            "日本語_text",
+            "x_y",
+            "a_b_c",
+            "get_a_b",
+        ];
+
+        // every input, and every converter's output for it
+        let mut all: Vec<String> = inputs.iter().map(|s| String::from(*s)).collect();
+        for input in inputs {
+            for (case, _) in CASES {
+                all.push(case(MStr::new_borrowed(input)).into_string());
+            }
+        }
+
+        for input in &all {
+            for (case, joins) in CASES {
+                if joins && runs_together(input) {
+                    continue;
+                }
+                let once = case(MStr::new_borrowed(input));
+                let twice = case(MStr::new_borrowed(&once));
+                assert_eq!(twice, once, "of {input:?}");
+                assert!(twice.is_borrowed());
+            }
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        // converting through another case first doesn't lose any word boundaries
+        for input in [
+            "fooBarBaz",
+            "HTTP_SERVER",
+            "x509-cert",
+            "9_lives",
+            "2fa_token",
+            "日本語_text",
+            "x_y",
+            "a_b_c",
+            "get_a_b",
+        ] {
+            for (via, joins) in CASES {
+                if joins && runs_together(input) {
+                    continue;
+                }
+                let via = via(MStr::new_borrowed(input));
+                for (case, _) in CASES {
+                    assert_eq!(
+                        case(MStr::new_borrowed(&via)),
+                        case(MStr::new_borrowed(input)),
+                        "{input:?} via {via:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn owned_keeps_allocation() {
+        let owned = MStr::new_owned("already_snake");
+        let ptr = owned.as_ptr();
+        let s = to_snake_case(owned);
+        assert!(s.is_owned());
+        assert_eq!(s.as_ptr(), ptr);
+    }
+}
@@ -0,0 +1,520 @@
+//! Case-insensitive string keys.
+//!
+//! [`CaseInsensitive<T, M>`](CaseInsensitive) wraps a string type `T`,
+//! and compares, orders and hashes it ignoring case, without allocating.
+//! The mode `M` picks what "ignoring case" means:
+//! - [`Ascii`] (the default) only folds ASCII letters, and is the right choice for protocol text
+//!   like HTTP header names.
+//! - [`Unicode`] folds every character, and is the right choice for user-facing text.
+//!
+//! [`UniCaseMStr`](crate::UniCaseMStr) is a shorthand for `CaseInsensitive<MStr<'a>, Unicode>`.
+//!
+//! Owned keys implement `Borrow<CaseInsensitive<str, M>>`,
+//! so maps can be queried with a plain `&str` through [`CaseInsensitive::new_ref`].
+//! This also makes [hashbrown](https://docs.rs/hashbrown) and [indexmap](https://docs.rs/indexmap)'s
+//! `Equivalent` lookups work, through their blanket impls.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! # use std::collections::HashMap;
+//! use mstr::case_insensitive::CaseInsensitive;
+//!
+//! let mut headers = HashMap::new();
+//! headers.insert(CaseInsensitive::<_>::new(MStr::new_borrowed("Content-Type")), "text/plain");
+//!
+//! assert_eq!(headers[CaseInsensitive::new_ref("content-type")], "text/plain");
+//! assert_eq!(headers.get(CaseInsensitive::new_ref("CONTENT-TYPE")), Some(&"text/plain"));
+//! ```
+
+use crate::MStr;
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::{fmt, iter};
+
+// ===== Modes =====
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// How a [`CaseInsensitive`] folds case. Implemented by [`Ascii`] and [`Unicode`].
+///
+/// This trait is sealed, and can't be implemented outside this crate.
+pub trait CaseMode: private::Sealed {
+    /// Checks if `a` and `b` are equal, ignoring case.
+    fn eq(a: &str, b: &str) -> bool;
+
+    /// Compares `a` and `b`, ignoring case.
+    fn cmp(a: &str, b: &str) -> Ordering;
+
+    /// Feeds `s` into `state`, ignoring case.
+    fn hash<H: Hasher>(s: &str, state: &mut H);
+}
+
+/// Only ASCII letters are compared case-insensitively. Every other character must match exactly.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ascii;
+
+/// Every character is compared case-insensitively.
+///
+/// Strings are compared as if each character was converted to uppercase, and then to lowercase
+/// (using [`char::to_uppercase`] and [`char::to_lowercase`]).
+/// This closely follows Unicode's full case folding (`"ß"` matches `"SS"`, `"ς"` matches `"Σ"`),
+/// without needing any tables beyond the ones in `core`.
+/// The one exception is handled explicitly: `"ẞ"` (capital sharp s) lowercases to `"ß"`,
+/// but is folded to `"ss"` like `"ß"` is, so `"ẞ"`, `"ß"` and `"SS"` all match.
+///
+/// No normalization is done, so composed and decomposed characters are never equal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Unicode;
+
+impl private::Sealed for Ascii {}
+impl private::Sealed for Unicode {}
+
+impl CaseMode for Ascii {
+    #[inline]
+    fn eq(a: &str, b: &str) -> bool {
+        a.eq_ignore_ascii_case(b)
+    }
+
+    #[inline]
+    fn cmp(a: &str, b: &str) -> Ordering {
+        let a = a.bytes().map(|b| b.to_ascii_lowercase());
+        let b = b.bytes().map(|b| b.to_ascii_lowercase());
+        a.cmp(b)
+    }
+
+    #[inline]
+    fn hash<H: Hasher>(s: &str, state: &mut H) {
+        for b in s.bytes() {
+            state.write_u8(b.to_ascii_lowercase());
+        }
+        // same terminator as str's Hash impl, so ("ab", "c") and ("a", "bc") hash differently
+        state.write_u8(0xff);
+    }
+}
+
+impl Unicode {
+    #[inline]
+    fn fold(s: &str) -> impl Iterator<Item = char> + '_ {
+        s.chars().flat_map(Unicode::fold_char)
+    }
+
+    #[inline]
+    pub(crate) fn fold_char(c: char) -> impl Iterator<Item = char> {
+        // ẞ lowercases to ß, which only becomes "ss" after uppercasing, so fold it as ß
+        let c = if c == '\u{1E9E}' { 'ß' } else { c };
+        c.to_uppercase().flat_map(char::to_lowercase)
+    }
+}
+
+impl CaseMode for Unicode {
+    #[inline]
+    fn eq(a: &str, b: &str) -> bool {
+        // fast path: ascii only strings fold the same as in ascii mode
+        if a.is_ascii() && b.is_ascii() {
+            return a.eq_ignore_ascii_case(b);
+        }
+        iter::Iterator::eq(Unicode::fold(a), Unicode::fold(b))
+    }
+
+    #[inline]
+    fn cmp(a: &str, b: &str) -> Ordering {
+        Unicode::fold(a).cmp(Unicode::fold(b))
+    }
+
+    #[inline]
+    fn hash<H: Hasher>(s: &str, state: &mut H) {
+        for c in Unicode::fold(s) {
+            state.write_u32(c as u32);
+        }
+        state.write_u8(0xff);
+    }
+}
+
+// ===== CaseInsensitive =====
+
+/// A string that is compared, ordered and hashed ignoring case.
+///
+/// `T` is any string type (`MStr`, `String`, `&str`, `str`, ...),
+/// and `M` is the [`CaseMode`], [`Ascii`] by default.
+/// The original string is kept as-is, and can be read with [`as_inner`](CaseInsensitive::as_inner)
+/// or through `Deref`.
+///
+/// See the [module docs](self) for more info.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::case_insensitive::{CaseInsensitive, Unicode};
+///
+/// let a = CaseInsensitive::<_>::new(MStr::new_borrowed("Frog"));
+/// assert_eq!(a, CaseInsensitive::new("FROG"));
+/// assert_eq!(a.as_inner(), "Frog");
+///
+/// let b = CaseInsensitive::<_, Unicode>::new("STRASSE");
+/// assert_eq!(b, CaseInsensitive::new("straße"));
+/// ```
+#[repr(transparent)]
+pub struct CaseInsensitive<T: ?Sized, M = Ascii> {
+    // fn() -> M so the mode doesn't affect Send/Sync
+    _mode: PhantomData<fn() -> M>,
+    inner: T,
+}
+
+impl<T, M> CaseInsensitive<T, M> {
+    /// Wraps `inner`.
+    #[inline]
+    #[must_use]
+    pub const fn new(inner: T) -> Self {
+        CaseInsensitive {
+            _mode: PhantomData,
+            inner,
+        }
+    }
+
+    /// Unwraps the inner string.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<M> CaseInsensitive<str, M> {
+    /// Wraps a `&str` without copying it.
+    ///
+    /// This is mostly useful for looking up keys in a map.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// # use std::collections::BTreeSet;
+    /// use mstr::case_insensitive::CaseInsensitive;
+    ///
+    /// let mut set = BTreeSet::new();
+    /// set.insert(CaseInsensitive::<_>::new(MStr::new_owned("Accept")));
+    ///
+    /// assert!(set.contains(CaseInsensitive::new_ref("ACCEPT")));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new_ref(s: &str) -> &Self {
+        // SAFETY: CaseInsensitive is repr(transparent) over str (the other field is a ZST)
+        unsafe { &*(s as *const str as *const CaseInsensitive<str, M>) }
+    }
+}
+
+impl<T: ?Sized, M> CaseInsensitive<T, M> {
+    /// Gets a reference to the inner string.
+    #[inline]
+    #[must_use]
+    pub const fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets the inner string as a `&str`.
+    #[inline]
+    #[must_use]
+    pub fn as_str(&self) -> &str
+    where
+        T: AsRef<str>,
+    {
+        self.inner.as_ref()
+    }
+}
+
+// ===== Trait Impls =====
+
+impl<T: Clone, M> Clone for CaseInsensitive<T, M> {
+    #[inline]
+    fn clone(&self) -> Self {
+        CaseInsensitive::new(self.inner.clone())
+    }
+}
+
+impl<T: Copy, M> Copy for CaseInsensitive<T, M> {}
+
+impl<T: Default, M> Default for CaseInsensitive<T, M> {
+    #[inline]
+    fn default() -> Self {
+        CaseInsensitive::new(T::default())
+    }
+}
+
+impl<T, M> From<T> for CaseInsensitive<T, M> {
+    #[inline]
+    fn from(inner: T) -> Self {
+        CaseInsensitive::new(inner)
+    }
+}
+
+impl<T: ?Sized, M> Deref for CaseInsensitive<T, M> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+// -- Format --
+
+impl<T: Debug + ?Sized, M> Debug for CaseInsensitive<T, M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+
+impl<T: Display + ?Sized, M> Display for CaseInsensitive<T, M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.inner, f)
+    }
+}
+
+// -- Borrow --
+
+// these can't be a blanket impl over T: Borrow<str>, since that would overlap with Borrow<T> for T
+macro_rules! impl_borrow {
+    ($($t:ty),*) => {
+        $(
+            impl<M> Borrow<CaseInsensitive<str, M>> for CaseInsensitive<$t, M> {
+                #[inline]
+                fn borrow(&self) -> &CaseInsensitive<str, M> {
+                    CaseInsensitive::new_ref(self.inner.borrow())
+                }
+            }
+        )*
+    };
+}
+
+impl_borrow!(MStr<'_>, String, Box<str>, &str);
+
+// -- Hash, Eq & Ord --
+
+impl<T: AsRef<str> + ?Sized, M: CaseMode> Hash for CaseInsensitive<T, M> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        M::hash(self.inner.as_ref(), state);
+    }
+}
+
+impl<T: AsRef<str> + ?Sized, M: CaseMode> Eq for CaseInsensitive<T, M> {}
+
+impl<T, U, M> PartialEq<CaseInsensitive<U, M>> for CaseInsensitive<T, M>
+where
+    T: AsRef<str> + ?Sized,
+    U: AsRef<str> + ?Sized,
+    M: CaseMode,
+{
+    #[inline]
+    fn eq(&self, other: &CaseInsensitive<U, M>) -> bool {
+        M::eq(self.inner.as_ref(), other.inner.as_ref())
+    }
+}
+
+impl<T: AsRef<str> + ?Sized, M: CaseMode> Ord for CaseInsensitive<T, M> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        M::cmp(self.inner.as_ref(), other.inner.as_ref())
+    }
+}
+
+impl<T, U, M> PartialOrd<CaseInsensitive<U, M>> for CaseInsensitive<T, M>
+where
+    T: AsRef<str> + ?Sized,
+    U: AsRef<str> + ?Sized,
+    M: CaseMode,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &CaseInsensitive<U, M>) -> Option<Ordering> {
+        Some(M::cmp(self.inner.as_ref(), other.inner.as_ref()))
+    }
+}
+
+// ===== serde =====
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::*;
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, Serializer};
+
+    impl<T: Serialize + ?Sized, M> Serialize for CaseInsensitive<T, M> {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            self.inner.serialize(s)
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>, M> Deserialize<'de> for CaseInsensitive<T, M> {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            T::deserialize(d).map(CaseInsensitive::new)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use serde_test::{assert_tokens, Token};
+
+        #[test]
+        fn transparent() {
+            assert_tokens(
+                &CaseInsensitive::<_>::new(MStr::from("Roar")),
+                &[Token::Str("Roar")],
+            );
+        }
+    }
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UniCaseMStr;
+    use alloc::collections::BTreeMap;
+    use alloc::vec::Vec;
+    use core::hash::BuildHasher;
+    use std::collections::hash_map::RandomState;
+    use std::collections::HashMap;
+
+    fn hash<T: Hash + ?Sized>(s: &RandomState, t: &T) -> u64 {
+        let mut h = s.build_hasher();
+        t.hash(&mut h);
+        h.finish()
+    }
+
+    fn ascii(s: &str) -> CaseInsensitive<&str> {
+        CaseInsensitive::new(s)
+    }
+
+    fn unicode(s: &str) -> CaseInsensitive<&str, Unicode> {
+        CaseInsensitive::new(s)
+    }
+
+    #[test]
+    fn ascii_mode() {
+        assert_eq!(ascii("Content-Type"), ascii("content-TYPE"));
+        assert_ne!(ascii("content-type"), ascii("content-typ"));
+        assert_ne!(ascii("straße"), ascii("STRASSE"));
+        // non-ascii letters are compared exactly
+        assert_ne!(ascii("é"), ascii("É"));
+
+        assert!(ascii("apple") < ascii("Banana"));
+        assert!(ascii("B") > ascii("a"));
+        assert_eq!(ascii("ABC").cmp(&ascii("abc")), Ordering::Equal);
+    }
+
+    #[test]
+    fn unicode_mode() {
+        assert_eq!(unicode("Content-Type"), unicode("content-TYPE"));
+        assert_eq!(unicode("é"), unicode("É"));
+        assert_eq!(unicode("straße"), unicode("STRASSE"));
+        assert_eq!(unicode("ΣΊΣΥΦΟΣ"), unicode("σίσυφος"));
+        assert_ne!(unicode("e\u{301}"), unicode("é"));
+
+        assert!(unicode("Éa") < unicode("éb"));
+        assert_eq!(unicode("STRASSE").cmp(&unicode("straße")), Ordering::Equal);
+    }
+
+    #[test]
+    fn capital_sharp_s() {
+        let s = RandomState::new();
+
+        for (a, b) in [
+            ("ẞ", "ß"),
+            ("ẞ", "SS"),
+            ("STRAẞE", "strasse"),
+            ("STRAẞE", "Straße"),
+        ] {
+            assert_eq!(unicode(a), unicode(b), "{a:?} {b:?}");
+            assert_eq!(unicode(a).cmp(&unicode(b)), Ordering::Equal, "{a:?} {b:?}");
+            assert_eq!(hash(&s, &unicode(a)), hash(&s, &unicode(b)), "{a:?} {b:?}");
+        }
+    }
+
+    #[test]
+    fn hash_matches_eq() {
+        let s = RandomState::new();
+
+        for (a, b) in [("Content-Type", "content-type"), ("X", "x"), ("", "")] {
+            assert_eq!(hash(&s, &ascii(a)), hash(&s, &ascii(b)));
+            assert_eq!(hash(&s, &unicode(a)), hash(&s, &unicode(b)));
+        }
+
+        assert_eq!(hash(&s, &unicode("straße")), hash(&s, &unicode("STRASSE")));
+        assert_ne!(hash(&s, &ascii("a")), hash(&s, &ascii("b")));
+
+        // the borrowed form has to hash the same as the owned form
+        let owned = CaseInsensitive::<_>::new(MStr::new_owned("Accept"));
+        assert_eq!(
+            hash(&s, &owned),
+            hash(&s, CaseInsensitive::<str>::new_ref("ACCEPT"))
+        );
+    }
+
+    #[test]
+    fn hash_map_lookup() {
+        let mut map = HashMap::new();
+        map.insert(UniCaseMStr::new(MStr::new_borrowed("Straße")), 1);
+        map.insert(UniCaseMStr::new(MStr::new_owned("Host")), 2);
+
+        assert_eq!(map.get(CaseInsensitive::new_ref("STRASSE")), Some(&1));
+        assert_eq!(map.get(CaseInsensitive::new_ref("host")), Some(&2));
+        assert_eq!(map.get(CaseInsensitive::new_ref("hos")), None);
+
+        // the original key is kept
+        let keys: Vec<_> = map.keys().map(|k| k.as_str()).collect();
+        assert!(keys.contains(&"Straße"));
+    }
+
+    #[test]
+    #[cfg(feature = "hashbrown")]
+    fn hashbrown_lookup() {
+        let mut map = hashbrown::HashMap::<UniCaseMStr<'_>, i32>::default();
+        map.insert(UniCaseMStr::new(MStr::new_borrowed("Host")), 1);
+
+        assert_eq!(map.get(CaseInsensitive::new_ref("HOST")), Some(&1));
+    }
+
+    #[test]
+    fn btree_map_lookup() {
+        let mut map = BTreeMap::new();
+        map.insert(CaseInsensitive::<_>::new(String::from("b")), 1);
+        map.insert(CaseInsensitive::new(String::from("A")), 2);
+
+        assert_eq!(map.get(CaseInsensitive::new_ref("B")), Some(&1));
+        let keys: Vec<_> = map.keys().map(|k| k.as_str()).collect();
+        assert_eq!(keys, ["A", "b"]);
+    }
+
+    #[test]
+    fn accessors() {
+        let s = UniCaseMStr::new(MStr::new_borrowed("Frog"));
+
+        assert_eq!(s.as_inner(), "Frog");
+        assert_eq!(s.as_str(), "Frog");
+        assert_eq!(s.len(), 4);
+        assert_eq!(alloc::format!("{s:?} {s}"), "\"Frog\" Frog");
+        assert!(s.into_inner().is_borrowed());
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+
+        assert_send_sync::<UniCaseMStr<'_>>();
+        assert_send_sync::<CaseInsensitive<str>>();
+    }
+}
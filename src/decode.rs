@@ -0,0 +1,333 @@
+use crate::MStr;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::iter::FusedIterator;
+use core::{char, fmt, str};
+
+// ===== MStr Constructors =====
+
+impl<'a> MStr<'a> {
+    /// Decodes UTF-16 into an owned `MStr`.
+    ///
+    /// If every code unit is ASCII, the string is built directly, without decoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `units` contains an unpaired surrogate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let units: Vec<u16> = "frog 🐸".encode_utf16().collect();
+    /// assert_eq!(MStr::from_utf16(&units).unwrap(), "frog 🐸");
+    ///
+    /// let err = MStr::from_utf16(&[0x66, 0xD83D, 0x66]).unwrap_err();
+    /// assert_eq!(err.index(), 1);
+    /// ```
+    pub fn from_utf16(units: &[u16]) -> Result<MStr<'a>, FromUtf16Error> {
+        if let Some(s) = ascii_utf16(units) {
+            return Ok(MStr::new_owned(s));
+        }
+
+        let mut s = String::with_capacity(units.len());
+        let mut index = 0;
+        for c in char::decode_utf16(units.iter().copied()) {
+            match c {
+                Ok(c) => {
+                    s.push(c);
+                    index += c.len_utf16();
+                }
+                Err(_) => return Err(FromUtf16Error { index }),
+            }
+        }
+        Ok(MStr::new_owned(s))
+    }
+
+    /// Decodes UTF-16 into an owned `MStr`, replacing unpaired surrogates with `U+FFFD`.
+    ///
+    /// If every code unit is ASCII, the string is built directly, without decoding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::from_utf16_lossy(&[0x66, 0xD83D, 0x66]);
+    /// assert_eq!(s, "f\u{FFFD}f");
+    /// ```
+    #[must_use]
+    pub fn from_utf16_lossy(units: &[u16]) -> MStr<'a> {
+        if let Some(s) = ascii_utf16(units) {
+            return MStr::new_owned(s);
+        }
+
+        let s: String = char::decode_utf16(units.iter().copied())
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+        MStr::new_owned(s)
+    }
+
+    /// Decodes Latin-1 (ISO-8859-1) into an `MStr`.
+    ///
+    /// Every byte maps to the Unicode code point with the same value, so this never fails.
+    /// If every byte is ASCII, the returned `MStr` borrows `bytes` (ASCII is the same in both encodings).
+    /// Otherwise a new owned string is allocated.
+    ///
+    /// Note that this is *not* Windows-1252, which differs in `0x80..=0x9F`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::from_latin1(b"caf\xe9");
+    /// assert_eq!(s, "café");
+    /// assert!(s.is_owned());
+    ///
+    /// let s = MStr::from_latin1(b"cafe");
+    /// assert!(s.is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn from_latin1(bytes: &'a [u8]) -> MStr<'a> {
+        if bytes.is_ascii() {
+            // SAFETY: ASCII is valid UTF-8
+            return MStr::new_borrowed(unsafe { str::from_utf8_unchecked(bytes) });
+        }
+
+        // every non-ascii byte becomes 2 bytes of UTF-8
+        let extra = bytes.iter().filter(|b| !b.is_ascii()).count();
+        let mut s = String::with_capacity(bytes.len() + extra);
+        s.extend(bytes.iter().map(|&b| char::from(b)));
+        MStr::new_owned(s)
+    }
+
+    /// Returns an iterator over the UTF-16 code units of this string.
+    ///
+    /// Unlike [`str::encode_utf16`], the returned iterator knows its exact length,
+    /// which is counted up front (with a fast path for ASCII strings).
+    ///
+    /// This is named differently from `str::encode_utf16` (which `MStr` derefs to),
+    /// so that `mstr.encode_utf16()` still returns a [`str::EncodeUtf16`], without counting anything.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("a🐸");
+    /// let units = s.encode_utf16_exact();
+    /// assert_eq!(units.len(), 3);
+    /// assert_eq!(units.collect::<Vec<_>>(), [0x61, 0xD83D, 0xDC38]);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn encode_utf16_exact(&self) -> EncodeUtf16<'_> {
+        let s = self.as_str();
+        let len = if s.is_ascii() {
+            s.len()
+        } else {
+            s.chars().map(char::len_utf16).sum()
+        };
+
+        EncodeUtf16 {
+            inner: s.encode_utf16(),
+            len,
+        }
+    }
+}
+
+// builds a string directly if every unit is ascii
+#[inline]
+fn ascii_utf16(units: &[u16]) -> Option<String> {
+    if !units.iter().all(|&u| u < 0x80) {
+        return None;
+    }
+
+    let bytes: Vec<u8> = units.iter().map(|&u| u as u8).collect();
+    // SAFETY: every byte is ASCII
+    Some(unsafe { String::from_utf8_unchecked(bytes) })
+}
+
+// ===== EncodeUtf16 =====
+
+/// An iterator over the UTF-16 code units of an `MStr`.
+///
+/// Created by [`MStr::encode_utf16_exact`].
+#[derive(Clone, Debug)]
+pub struct EncodeUtf16<'s> {
+    inner: str::EncodeUtf16<'s>,
+    len: usize,
+}
+
+impl Iterator for EncodeUtf16<'_> {
+    type Item = u16;
+
+    #[inline]
+    fn next(&mut self) -> Option<u16> {
+        let u = self.inner.next()?;
+        self.len -= 1;
+        Some(u)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl ExactSizeIterator for EncodeUtf16<'_> {}
+
+impl FusedIterator for EncodeUtf16<'_> {}
+
+// ===== Error =====
+
+/// The error returned by [`MStr::from_utf16`] when the input has an unpaired surrogate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FromUtf16Error {
+    index: usize,
+}
+
+impl FromUtf16Error {
+    /// Returns the index of the unpaired surrogate code unit.
+    ///
+    /// Everything before it is valid UTF-16.
+    #[inline]
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl Display for FromUtf16Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid utf-16: unpaired surrogate at index {}",
+            self.index
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FromUtf16Error {}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    // fixtures are written by Windows-style tools: UTF-16LE with CRLF line endings
+    // (utf16le.txt starts with a BOM, utf16le-ascii.txt doesn't)
+    const UTF16LE: &[u8] = include_bytes!("../tests/fixtures/utf16le.txt");
+    const UTF16LE_ASCII: &[u8] = include_bytes!("../tests/fixtures/utf16le-ascii.txt");
+    const LATIN1: &[u8] = include_bytes!("../tests/fixtures/latin1.txt");
+
+    fn le_units(bytes: &[u8]) -> Vec<u16> {
+        bytes
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .collect()
+    }
+
+    #[test]
+    fn utf16_fixture() {
+        let units = le_units(UTF16LE);
+        assert_eq!(units[0], 0xFEFF);
+
+        let s = MStr::from_utf16(&units[1..]).unwrap();
+        assert_eq!(
+            s,
+            "Hello, world!\r\nGrüße aus Köln\r\n日本語のテキスト\r\n🐸 frogs\r\n"
+        );
+        assert!(s.is_owned());
+        assert_eq!(MStr::from_utf16_lossy(&units[1..]), s);
+
+        // and back again
+        assert!(s.encode_utf16_exact().eq(units[1..].iter().copied()));
+        assert_eq!(s.encode_utf16_exact().len(), units.len() - 1);
+    }
+
+    #[test]
+    fn utf16_ascii_fixture() {
+        let units = le_units(UTF16LE_ASCII);
+
+        let s = MStr::from_utf16(&units).unwrap();
+        assert_eq!(s, "plain ASCII text\r\n");
+        assert_eq!(MStr::from_utf16_lossy(&units), s);
+        assert!(s.encode_utf16_exact().eq(units.iter().copied()));
+    }
+
+    #[test]
+    fn utf16_errors() {
+        // lone high surrogate, lone low surrogate, high surrogate at the end
+        let err = MStr::from_utf16(&[0x61, 0xD83D, 0x61]).unwrap_err();
+        assert_eq!(err.index(), 1);
+        let err = MStr::from_utf16(&[0xD83D, 0xDC38, 0xDC38]).unwrap_err();
+        assert_eq!(err.index(), 2);
+        let err = MStr::from_utf16(&[0x61, 0x62, 0xD83D]).unwrap_err();
+        assert_eq!(err.index(), 2);
+
+        assert_eq!(
+            alloc::format!("{err}"),
+            "invalid utf-16: unpaired surrogate at index 2"
+        );
+
+        assert_eq!(
+            MStr::from_utf16_lossy(&[0xDC38, 0xD83D, 0xDC38, 0xD83D]),
+            "\u{FFFD}🐸\u{FFFD}"
+        );
+    }
+
+    #[test]
+    fn utf16_empty() {
+        assert_eq!(MStr::from_utf16(&[]).unwrap(), "");
+        assert_eq!(MStr::new_borrowed("").encode_utf16_exact().len(), 0);
+    }
+
+    #[test]
+    fn latin1_fixture() {
+        let s = MStr::from_latin1(LATIN1);
+        assert_eq!(s, "Café crème, naïve façade, £5 ±1 ©\n");
+        assert!(s.is_owned());
+        assert_eq!(s.chars().count(), LATIN1.len());
+    }
+
+    #[test]
+    fn latin1_all_bytes() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let s = MStr::from_latin1(&bytes);
+
+        assert!(s.chars().map(u32::from).eq(0..=255));
+        assert_eq!(s.len(), 128 + 2 * 128);
+    }
+
+    #[test]
+    fn latin1_ascii_borrows() {
+        let bytes = vec![b'a'; 100];
+        let s = MStr::from_latin1(&bytes);
+
+        assert!(s.is_borrowed());
+        assert_eq!(s.as_ptr(), bytes.as_ptr());
+    }
+
+    #[test]
+    fn encode_utf16_exact_size() {
+        let s = MStr::new_owned("a🐸é");
+        let mut iter = s.encode_utf16_exact();
+
+        assert_eq!(iter.len(), 4);
+        iter.next();
+        iter.next();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.by_ref().count(), 2);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn str_encode_utf16_not_shadowed() {
+        let s = MStr::new_borrowed("a🐸");
+        let units: str::EncodeUtf16<'_> = s.encode_utf16();
+        assert!(units.eq(s.encode_utf16_exact()));
+    }
+}
@@ -0,0 +1,334 @@
+//! Decoding legacy charsets into `MStr` with [encoding_rs](https://docs.rs/encoding_rs).
+//!
+//! The [`MStr::decode`] family wraps the matching [`Encoding`] methods,
+//! and returns a borrowed `MStr<'a>` whenever the input can be used as-is
+//! (UTF-8 input, or ASCII input in an ASCII-compatible encoding).
+//! [`StringDecoder`] decodes a stream of chunks into a single owned `MStr`.
+//!
+//! Requires the `encoding_rs` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use encoding_rs::{SHIFT_JIS, WINDOWS_1252};
+//!
+//! let (s, had_errors) = MStr::decode_with_bom_removal(b"\x83n\x83\x8D\x81[", SHIFT_JIS);
+//! assert_eq!(s, "ハロー");
+//! assert!(s.is_owned() && !had_errors);
+//!
+//! // ascii is the same in windows-1252, so nothing is copied
+//! let (s, _) = MStr::decode_with_bom_removal(b"plain text", WINDOWS_1252);
+//! assert!(s.is_borrowed());
+//! ```
+
+use crate::MStr;
+use alloc::string::String;
+use encoding_rs::{CoderResult, Decoder, Encoding};
+
+// ===== MStr Constructors =====
+
+impl<'a> MStr<'a> {
+    /// Decodes `bytes` with BOM sniffing, replacing malformed sequences with `U+FFFD`.
+    ///
+    /// If `bytes` starts with a UTF-8 or UTF-16 BOM, that encoding is used instead of `encoding`,
+    /// and the BOM is removed.
+    /// Returns the decoded string, the encoding that was actually used, and whether there were malformed sequences.
+    ///
+    /// The returned `MStr` borrows `bytes` if no conversion was needed.
+    ///
+    /// See [`Encoding::decode`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// use encoding_rs::{UTF_8, WINDOWS_1252};
+    ///
+    /// let (s, encoding, had_errors) = MStr::decode(b"\xEF\xBB\xBFcaf\xC3\xA9", WINDOWS_1252);
+    /// assert_eq!(s, "café");
+    /// assert_eq!(encoding, UTF_8); // chosen by the BOM
+    /// assert!(s.is_borrowed() && !had_errors);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn decode(
+        bytes: &'a [u8],
+        encoding: &'static Encoding,
+    ) -> (MStr<'a>, &'static Encoding, bool) {
+        let (s, encoding, had_errors) = encoding.decode(bytes);
+        (MStr::new_cow(s), encoding, had_errors)
+    }
+
+    /// Decodes `bytes` with `encoding`, removing a BOM for that encoding if present,
+    /// and replacing malformed sequences with `U+FFFD`.
+    ///
+    /// Returns the decoded string, and whether there were malformed sequences.
+    ///
+    /// The returned `MStr` borrows `bytes` if no conversion was needed.
+    ///
+    /// See [`Encoding::decode_with_bom_removal`].
+    #[inline]
+    #[must_use]
+    pub fn decode_with_bom_removal(
+        bytes: &'a [u8],
+        encoding: &'static Encoding,
+    ) -> (MStr<'a>, bool) {
+        let (s, had_errors) = encoding.decode_with_bom_removal(bytes);
+        (MStr::new_cow(s), had_errors)
+    }
+
+    /// Decodes `bytes` with `encoding`, replacing malformed sequences with `U+FFFD`.
+    /// BOMs are decoded like any other character.
+    ///
+    /// Returns the decoded string, and whether there were malformed sequences.
+    ///
+    /// The returned `MStr` borrows `bytes` if no conversion was needed.
+    ///
+    /// See [`Encoding::decode_without_bom_handling`].
+    #[inline]
+    #[must_use]
+    pub fn decode_without_bom_handling(
+        bytes: &'a [u8],
+        encoding: &'static Encoding,
+    ) -> (MStr<'a>, bool) {
+        let (s, had_errors) = encoding.decode_without_bom_handling(bytes);
+        (MStr::new_cow(s), had_errors)
+    }
+
+    /// Decodes `bytes` with `encoding`, returning `None` if there are any malformed sequences.
+    /// BOMs are decoded like any other character.
+    ///
+    /// The returned `MStr` borrows `bytes` if no conversion was needed.
+    ///
+    /// See [`Encoding::decode_without_bom_handling_and_without_replacement`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// use encoding_rs::SHIFT_JIS;
+    ///
+    /// assert!(MStr::decode_strict(b"\x82\xA0", SHIFT_JIS).is_some());
+    /// assert!(MStr::decode_strict(b"\x82", SHIFT_JIS).is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn decode_strict(bytes: &'a [u8], encoding: &'static Encoding) -> Option<MStr<'a>> {
+        encoding
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .map(MStr::new_cow)
+    }
+}
+
+// ===== StringDecoder =====
+
+/// Decodes a stream of byte chunks into a single owned `MStr`.
+///
+/// This wraps an encoding_rs [`Decoder`], and takes care of sizing the output buffer.
+/// Sequences split between chunks are handled correctly.
+///
+/// # Examples
+///
+/// ```rust
+/// use encoding_rs::SHIFT_JIS;
+/// use mstr::encoding::StringDecoder;
+///
+/// let mut decoder = StringDecoder::new(SHIFT_JIS);
+/// // "ハ" is split across the chunks
+/// decoder.push(b"\x83");
+/// decoder.push(b"n\x83\x8D\x81[");
+///
+/// let (s, had_errors) = decoder.finish();
+/// assert_eq!(s, "ハロー");
+/// assert!(!had_errors);
+/// ```
+pub struct StringDecoder {
+    decoder: Decoder,
+    buf: String,
+    had_errors: bool,
+}
+
+impl StringDecoder {
+    /// Creates a new `StringDecoder` for `encoding`, with BOM sniffing.
+    ///
+    /// See [`Encoding::new_decoder`].
+    #[inline]
+    #[must_use]
+    pub fn new(encoding: &'static Encoding) -> StringDecoder {
+        StringDecoder::from_decoder(encoding.new_decoder())
+    }
+
+    /// Creates a new `StringDecoder` that uses an existing [`Decoder`].
+    ///
+    /// This can be used to choose how BOMs are handled
+    /// (e.g. with [`Encoding::new_decoder_without_bom_handling`]).
+    #[inline]
+    #[must_use]
+    pub fn from_decoder(decoder: Decoder) -> StringDecoder {
+        StringDecoder {
+            decoder,
+            buf: String::new(),
+            had_errors: false,
+        }
+    }
+
+    /// Decodes the next chunk of input.
+    ///
+    /// Malformed sequences are replaced with `U+FFFD`.
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.decode(chunk, false);
+    }
+
+    /// Returns the encoding being decoded.
+    ///
+    /// With BOM sniffing, this can change after the first few bytes.
+    #[inline]
+    #[must_use]
+    pub fn encoding(&self) -> &'static Encoding {
+        self.decoder.encoding()
+    }
+
+    /// Returns whether any malformed sequences have been replaced so far.
+    #[inline]
+    #[must_use]
+    pub fn had_errors(&self) -> bool {
+        self.had_errors
+    }
+
+    /// Finishes decoding, and returns the decoded string,
+    /// and whether there were any malformed sequences.
+    ///
+    /// Any incomplete sequence at the end of the input is replaced with `U+FFFD`.
+    #[must_use]
+    pub fn finish(mut self) -> (MStr<'static>, bool) {
+        self.decode(&[], true);
+        (MStr::new_owned(self.buf), self.had_errors)
+    }
+
+    fn decode(&mut self, src: &[u8], last: bool) {
+        let needed = self
+            .decoder
+            .max_utf8_buffer_length(src.len())
+            .expect("decoded string too large");
+        self.buf.reserve(needed);
+
+        let (result, read, had_errors) = self.decoder.decode_to_string(src, &mut self.buf, last);
+        // enough space was reserved for everything
+        debug_assert!(matches!(result, CoderResult::InputEmpty));
+        debug_assert_eq!(read, src.len());
+
+        self.had_errors |= had_errors;
+    }
+}
+
+impl core::fmt::Debug for StringDecoder {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("StringDecoder")
+            .field("encoding", &self.encoding())
+            .field("had_errors", &self.had_errors)
+            .finish_non_exhaustive()
+    }
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{SHIFT_JIS, UTF_16LE, UTF_8, WINDOWS_1252};
+
+    // "ハロー、世界" in Shift_JIS
+    const SJIS: &[u8] = b"\x83n\x83\x8D\x81[\x81A\x90\xA2\x8AE";
+
+    #[test]
+    fn decode_borrows_when_possible() {
+        let (s, encoding, had_errors) = MStr::decode(b"ascii", WINDOWS_1252);
+        assert!(s.is_borrowed());
+        assert_eq!(encoding, WINDOWS_1252);
+        assert!(!had_errors);
+
+        let (s, _, _) = MStr::decode("🐸".as_bytes(), UTF_8);
+        assert!(s.is_borrowed());
+
+        let (s, _) = MStr::decode_with_bom_removal(b"ascii", SHIFT_JIS);
+        assert!(s.is_borrowed());
+    }
+
+    #[test]
+    fn decode_legacy() {
+        let (s, had_errors) = MStr::decode_with_bom_removal(SJIS, SHIFT_JIS);
+        assert_eq!(s, "ハロー、世界");
+        assert!(s.is_owned());
+        assert!(!had_errors);
+
+        let (s, had_errors) = MStr::decode_without_bom_handling(b"\x80 caf\xE9", WINDOWS_1252);
+        assert_eq!(s, "€ café");
+        assert!(!had_errors);
+    }
+
+    #[test]
+    fn decode_bom_sniffing() {
+        let (s, encoding, _) = MStr::decode(b"\xFF\xFEh\0i\0", WINDOWS_1252);
+        assert_eq!(s, "hi");
+        assert_eq!(encoding, UTF_16LE);
+
+        // the BOM only gets removed if it matches the encoding
+        let (s, _) = MStr::decode_with_bom_removal(b"\xEF\xBB\xBFhi", UTF_8);
+        assert_eq!(s, "hi");
+        assert!(s.is_borrowed());
+
+        let (s, _) = MStr::decode_without_bom_handling(b"\xEF\xBB\xBFhi", UTF_8);
+        assert_eq!(s, "\u{FEFF}hi");
+    }
+
+    #[test]
+    fn decode_errors() {
+        let (s, had_errors) = MStr::decode_with_bom_removal(b"a\xFFb", UTF_8);
+        assert_eq!(s, "a\u{FFFD}b");
+        assert!(had_errors);
+
+        assert_eq!(MStr::decode_strict(b"a\xFFb", UTF_8), None);
+        assert_eq!(
+            MStr::decode_strict(SJIS, SHIFT_JIS).unwrap(),
+            "ハロー、世界"
+        );
+        assert!(MStr::decode_strict(b"ok", SHIFT_JIS).unwrap().is_borrowed());
+    }
+
+    #[test]
+    fn stream() {
+        // every possible split point, including splits inside a character
+        for i in 0..=SJIS.len() {
+            let mut decoder = StringDecoder::new(SHIFT_JIS);
+            decoder.push(&SJIS[..i]);
+            decoder.push(&SJIS[i..]);
+
+            let (s, had_errors) = decoder.finish();
+            assert_eq!(s, "ハロー、世界");
+            assert!(!had_errors);
+        }
+    }
+
+    #[test]
+    fn stream_bom_and_errors() {
+        let mut decoder = StringDecoder::new(WINDOWS_1252);
+        decoder.push(b"\xFF");
+        decoder.push(b"\xFEh\0i\0");
+        assert_eq!(decoder.encoding(), UTF_16LE);
+        assert!(!decoder.had_errors());
+
+        // a dangling half of a code unit
+        decoder.push(b"!");
+        let (s, had_errors) = decoder.finish();
+        assert_eq!(s, "hi\u{FFFD}");
+        assert!(had_errors);
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<StringDecoder>();
+    }
+}
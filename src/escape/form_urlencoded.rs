@@ -0,0 +1,281 @@
+//! Parsing and serializing `application/x-www-form-urlencoded` data (query strings and form bodies).
+//!
+//! This follows the [WHATWG URL standard](https://url.spec.whatwg.org/#application/x-www-form-urlencoded):
+//! pairs are separated by `&`, names and values by `=`, `+` means a space,
+//! and everything else is [percent-encoded](super::percent).
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use mstr::escape::form_urlencoded;
+//!
+//! let mut pairs = form_urlencoded::parse("q=frog+facts&lang=en&page=%32");
+//!
+//! let (name, value) = pairs.next().unwrap();
+//! assert_eq!((name.as_str(), value.as_str()), ("q", "frog facts"));
+//! assert!(name.is_borrowed() && value.is_owned());
+//!
+//! // nothing to decode, so nothing is allocated
+//! let (name, value) = pairs.next().unwrap();
+//! assert!(name.is_borrowed() && value.is_borrowed());
+//!
+//! assert_eq!(pairs.next().unwrap().1, "2");
+//! assert_eq!(pairs.next(), None);
+//!
+//! let query = form_urlencoded::serialize([("q", "frog facts"), ("tags", "🐸&🐢")]);
+//! assert_eq!(query, "q=frog+facts&tags=%F0%9F%90%B8%26%F0%9F%90%A2");
+//! ```
+
+use super::percent::{self, AsciiSet, COMPONENT};
+use crate::MStr;
+use alloc::string::String;
+use core::iter::FusedIterator;
+
+/// The bytes that are percent-encoded in form data.
+///
+/// This is [`COMPONENT`] with `!`, `'`, `(`, `)` and `~` added.
+/// Space is in the set too, but [`encode`] writes it as `+` instead.
+pub const FORM: AsciiSet = COMPONENT.add(b'!').add(b'\'').add(b'(').add(b')').add(b'~');
+
+// ===== Parse =====
+
+/// Parses `input` into an iterator of decoded `(name, value)` pairs.
+///
+/// Empty pairs (`a=1&&b=2`) are skipped, and a pair without `=` has an empty value.
+/// Names and values borrow `input` if they have nothing to decode.
+///
+/// Like browsers, decoding never fails:
+/// a `%` that isn't followed by two hex digits is kept as-is,
+/// and invalid UTF-8 is replaced with `U+FFFD`.
+///
+/// Don't include the leading `?` of a query string.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::form_urlencoded;
+///
+/// let pairs: Vec<_> = form_urlencoded::parse("a=1&&flag&b=x%3Dy").collect();
+/// assert_eq!(
+///     pairs,
+///     [
+///         (MStr::new_borrowed("a"), MStr::new_borrowed("1")),
+///         (MStr::new_borrowed("flag"), MStr::new_borrowed("")),
+///         (MStr::new_borrowed("b"), MStr::new_borrowed("x=y")),
+///     ]
+/// );
+/// ```
+#[inline]
+#[must_use]
+pub fn parse(input: &str) -> Parse<'_> {
+    Parse { input }
+}
+
+/// An iterator over the decoded `(name, value)` pairs of form data.
+///
+/// Created by [`parse`].
+#[derive(Clone, Debug)]
+pub struct Parse<'a> {
+    input: &'a str,
+}
+
+impl<'a> Iterator for Parse<'a> {
+    type Item = (MStr<'a>, MStr<'a>);
+
+    fn next(&mut self) -> Option<(MStr<'a>, MStr<'a>)> {
+        loop {
+            if self.input.is_empty() {
+                return None;
+            }
+
+            let (pair, rest) = self.input.split_once('&').unwrap_or((self.input, ""));
+            self.input = rest;
+
+            if pair.is_empty() {
+                continue;
+            }
+
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            return Some((
+                decode(MStr::new_borrowed(name)),
+                decode(MStr::new_borrowed(value)),
+            ));
+        }
+    }
+}
+
+impl FusedIterator for Parse<'_> {}
+
+// ===== Encode / Decode =====
+
+/// Encodes a single name or value as form data.
+///
+/// Spaces become `+`, and every byte in [`FORM`] (and every non-ASCII byte) is percent-encoded.
+///
+/// If nothing needs encoding, `s` is returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::form_urlencoded;
+///
+/// assert_eq!(form_urlencoded::encode(MStr::new_borrowed("a b+c")), "a+b%2Bc");
+/// assert!(form_urlencoded::encode(MStr::new_borrowed("a-b_c.d*")).is_borrowed());
+/// ```
+#[must_use]
+pub fn encode(s: MStr<'_>) -> MStr<'_> {
+    let Some(first) = s.bytes().position(|b| FORM.contains(b) || !b.is_ascii()) else {
+        return s;
+    };
+
+    let mut out = String::with_capacity(s.len() + s.len() / 4 + 8);
+    out.push_str(&s[..first]);
+    percent::encode_into(
+        &s.as_bytes()[first..],
+        &FORM.remove(b' '),
+        |b| if b == b' ' { b'+' } else { b },
+        &mut out,
+    );
+
+    MStr::new_owned(out)
+}
+
+/// Decodes a single name or value of form data.
+///
+/// `+` becomes a space, and percent-encoded bytes are decoded like [`parse`] does.
+///
+/// If nothing needs decoding, `s` is returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::form_urlencoded;
+///
+/// assert_eq!(form_urlencoded::decode(MStr::new_borrowed("a+b%2Bc")), "a b+c");
+/// ```
+#[must_use]
+pub fn decode(s: MStr<'_>) -> MStr<'_> {
+    percent::decode_lossy_impl(s, true)
+}
+
+/// Serializes `(name, value)` pairs as form data.
+///
+/// # Examples
+///
+/// ```rust
+/// use mstr::escape::form_urlencoded;
+///
+/// let body = form_urlencoded::serialize([("name", "Kermit"), ("says", "hi ho!")]);
+/// assert_eq!(body, "name=Kermit&says=hi+ho%21");
+/// ```
+#[must_use]
+pub fn serialize<I, K, V>(pairs: I) -> String
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut out = String::new();
+    for (name, value) in pairs {
+        if !out.is_empty() {
+            out.push('&');
+        }
+        out.push_str(&encode(MStr::new_borrowed(name.as_ref())));
+        out.push('=');
+        out.push_str(&encode(MStr::new_borrowed(value.as_ref())));
+    }
+    out
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn pairs(input: &str) -> Vec<(MStr<'_>, MStr<'_>)> {
+        parse(input).collect()
+    }
+
+    fn owned(name: &str, value: &str) -> (MStr<'static>, MStr<'static>) {
+        (MStr::new_owned(name), MStr::new_owned(value))
+    }
+
+    #[test]
+    fn parse_pairs() {
+        assert_eq!(pairs(""), []);
+        assert_eq!(pairs("&&"), []);
+        assert_eq!(pairs("a=1&b=2"), [owned("a", "1"), owned("b", "2")]);
+        assert_eq!(
+            pairs("a&=b&c="),
+            [owned("a", ""), owned("", "b"), owned("c", "")]
+        );
+        // only the first `=` splits
+        assert_eq!(pairs("a==b=c"), [owned("a", "=b=c")]);
+        assert_eq!(pairs("&a=1&"), [owned("a", "1")]);
+    }
+
+    #[test]
+    fn parse_decodes() {
+        assert_eq!(
+            pairs("na+me=caf%C3%A9+%26+cr%C3%A8me&%F0%9F%90%B8=1"),
+            [owned("na me", "café & crème"), owned("🐸", "1")]
+        );
+        // lenient, like browsers
+        assert_eq!(
+            pairs("p=100%&q=%FF%zz"),
+            [owned("p", "100%"), owned("q", "\u{FFFD}%zz")]
+        );
+    }
+
+    #[test]
+    fn parse_borrows() {
+        let input = "name=value&x=a+b";
+        let mut iter = parse(input);
+
+        let (name, value) = iter.next().unwrap();
+        assert!(name.is_borrowed() && value.is_borrowed());
+        assert_eq!(name.as_ptr(), input.as_ptr());
+
+        let (name, value) = iter.next().unwrap();
+        assert!(name.is_borrowed() && value.is_owned());
+
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn encode_form() {
+        assert_eq!(
+            encode(MStr::new_borrowed("a b&c=d+e/f?g~h!*'()é")),
+            "a+b%26c%3Dd%2Be%2Ff%3Fg%7Eh%21*%27%28%29%C3%A9"
+        );
+        assert!(encode(MStr::new_borrowed("AZaz09-._*")).is_borrowed());
+    }
+
+    #[test]
+    fn serialize_roundtrip() {
+        let input = [("a b", "c&d"), ("🐸", "1+1=2"), ("", ""), ("%41", "~")];
+        let s = serialize(input);
+        assert_eq!(s, "a+b=c%26d&%F0%9F%90%B8=1%2B1%3D2&=&%2541=%7E");
+
+        // the empty pair serializes as "=", which parses back
+        let parsed = pairs(&s);
+        assert_eq!(parsed.len(), input.len());
+        for ((name, value), (n, v)) in parsed.iter().zip(input) {
+            assert_eq!((name.as_str(), value.as_str()), (n, v));
+        }
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Parse<'_>>();
+    }
+}
@@ -0,0 +1,299 @@
+//! Escaping and unescaping HTML.
+//!
+//! [`escape_text`] is for text content, and [`escape_attr`] is for (quoted) attribute values.
+//! [`unescape`] decodes numeric character references and named entities.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use mstr::escape::html;
+//!
+//! let name = MStr::new_borrowed("Tom & Jerry");
+//! assert_eq!(html::escape_text(name), "Tom &amp; Jerry");
+//!
+//! // nothing to escape, so nothing is allocated
+//! let name = html::escape_text(MStr::new_borrowed("Frog"));
+//! assert!(name.is_borrowed());
+//!
+//! assert_eq!(html::unescape(MStr::new_borrowed("&lt;p&gt; &copy; &#x1F438;")), "<p> © 🐸");
+//! ```
+
+use super::{escape_chars, parse_char_ref, unescape_refs};
+use crate::MStr;
+use core::convert::Infallible;
+
+// ===== Escape =====
+
+/// Escapes `s` for use as HTML text content.
+///
+/// `&`, `<` and `>` are replaced with `&amp;`, `&lt;` and `&gt;`.
+/// Quotes are left as-is, so the result is **not** safe to use in an attribute value
+/// (use [`escape_attr`] for that).
+///
+/// If nothing needs escaping, `s` is returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::html;
+///
+/// assert_eq!(html::escape_text(MStr::new_borrowed("<b>\"hi\"</b>")), "&lt;b&gt;\"hi\"&lt;/b&gt;");
+/// ```
+#[must_use]
+pub fn escape_text(s: MStr<'_>) -> MStr<'_> {
+    escape_chars(
+        s,
+        |c| matches!(c, '&' | '<' | '>'),
+        |c, out| out.push_str(escape_char(c)),
+    )
+}
+
+/// Escapes `s` for use as a quoted HTML attribute value (with either `"` or `'` quotes).
+///
+/// `&`, `<`, `>`, `"` and `'` are replaced with `&amp;`, `&lt;`, `&gt;`, `&quot;` and `&#39;`.
+///
+/// If nothing needs escaping, `s` is returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::html;
+///
+/// assert_eq!(html::escape_attr(MStr::new_borrowed("it's \"fine\"")), "it&#39;s &quot;fine&quot;");
+/// ```
+#[must_use]
+pub fn escape_attr(s: MStr<'_>) -> MStr<'_> {
+    escape_chars(
+        s,
+        |c| matches!(c, '&' | '<' | '>' | '"' | '\''),
+        |c, out| out.push_str(escape_char(c)),
+    )
+}
+
+#[inline]
+fn escape_char(c: char) -> &'static str {
+    match c {
+        '&' => "&amp;",
+        '<' => "&lt;",
+        '>' => "&gt;",
+        '"' => "&quot;",
+        '\'' => "&#39;",
+        _ => unreachable!(),
+    }
+}
+
+// ===== Unescape =====
+
+/// Unescapes HTML text or attribute values.
+///
+/// Decodes numeric character references (`&#233;` and `&#xE9;`),
+/// and the named entities from HTML 4 (`&amp;`, `&nbsp;`, `&eacute;`, `&hellip;`, ...) plus `&apos;`.
+/// Numeric references to code points that aren't valid characters (`&#0;`, surrogates, anything above `U+10FFFF`)
+/// decode to `U+FFFD`.
+///
+/// Like a browser, this never fails: anything that isn't a recognized reference,
+/// including references without the final `;`, is kept as-is.
+///
+/// If `s` has no `&`, it is returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::html;
+///
+/// let s = html::unescape(MStr::new_borrowed("caf&eacute; &amp; cr&#232;me &unknown; &amp"));
+/// assert_eq!(s, "café & crème &unknown; &amp");
+/// ```
+#[must_use]
+pub fn unescape(s: MStr<'_>) -> MStr<'_> {
+    let result = unescape_refs::<Infallible>(s, |_, body, out| {
+        let Some(body) = body else {
+            return Ok(false);
+        };
+
+        let c = if body.starts_with('#') {
+            match parse_char_ref(body) {
+                Some(n) => char::from_u32(n)
+                    .filter(|&c| c != '\0')
+                    .unwrap_or(char::REPLACEMENT_CHARACTER),
+                None => return Ok(false),
+            }
+        } else {
+            match ENTITIES.binary_search_by(|(name, _)| (*name).cmp(body)) {
+                Ok(i) => ENTITIES[i].1,
+                Err(_) => return Ok(false),
+            }
+        };
+
+        out.push(c);
+        Ok(true)
+    });
+
+    match result {
+        Ok(s) => s,
+        Err(never) => match never {},
+    }
+}
+
+// the named entities from HTML 4.01, plus apos (sorted by name, for binary search)
+#[rustfmt::skip]
+static ENTITIES: [(&str, char); 253] = [
+    ("AElig", '\u{C6}'), ("Aacute", '\u{C1}'), ("Acirc", '\u{C2}'), ("Agrave", '\u{C0}'),
+    ("Alpha", '\u{391}'), ("Aring", '\u{C5}'), ("Atilde", '\u{C3}'), ("Auml", '\u{C4}'),
+    ("Beta", '\u{392}'), ("Ccedil", '\u{C7}'), ("Chi", '\u{3A7}'), ("Dagger", '\u{2021}'),
+    ("Delta", '\u{394}'), ("ETH", '\u{D0}'), ("Eacute", '\u{C9}'), ("Ecirc", '\u{CA}'),
+    ("Egrave", '\u{C8}'), ("Epsilon", '\u{395}'), ("Eta", '\u{397}'), ("Euml", '\u{CB}'),
+    ("Gamma", '\u{393}'), ("Iacute", '\u{CD}'), ("Icirc", '\u{CE}'), ("Igrave", '\u{CC}'),
+    ("Iota", '\u{399}'), ("Iuml", '\u{CF}'), ("Kappa", '\u{39A}'), ("Lambda", '\u{39B}'),
+    ("Mu", '\u{39C}'), ("Ntilde", '\u{D1}'), ("Nu", '\u{39D}'), ("OElig", '\u{152}'),
+    ("Oacute", '\u{D3}'), ("Ocirc", '\u{D4}'), ("Ograve", '\u{D2}'), ("Omega", '\u{3A9}'),
+    ("Omicron", '\u{39F}'), ("Oslash", '\u{D8}'), ("Otilde", '\u{D5}'), ("Ouml", '\u{D6}'),
+    ("Phi", '\u{3A6}'), ("Pi", '\u{3A0}'), ("Prime", '\u{2033}'), ("Psi", '\u{3A8}'),
+    ("Rho", '\u{3A1}'), ("Scaron", '\u{160}'), ("Sigma", '\u{3A3}'), ("THORN", '\u{DE}'),
+    ("Tau", '\u{3A4}'), ("Theta", '\u{398}'), ("Uacute", '\u{DA}'), ("Ucirc", '\u{DB}'),
+    ("Ugrave", '\u{D9}'), ("Upsilon", '\u{3A5}'), ("Uuml", '\u{DC}'), ("Xi", '\u{39E}'),
+    ("Yacute", '\u{DD}'), ("Yuml", '\u{178}'), ("Zeta", '\u{396}'), ("aacute", '\u{E1}'),
+    ("acirc", '\u{E2}'), ("acute", '\u{B4}'), ("aelig", '\u{E6}'), ("agrave", '\u{E0}'),
+    ("alefsym", '\u{2135}'), ("alpha", '\u{3B1}'), ("amp", '\u{26}'), ("and", '\u{2227}'),
+    ("ang", '\u{2220}'), ("apos", '\u{27}'), ("aring", '\u{E5}'), ("asymp", '\u{2248}'),
+    ("atilde", '\u{E3}'), ("auml", '\u{E4}'), ("bdquo", '\u{201E}'), ("beta", '\u{3B2}'),
+    ("brvbar", '\u{A6}'), ("bull", '\u{2022}'), ("cap", '\u{2229}'), ("ccedil", '\u{E7}'),
+    ("cedil", '\u{B8}'), ("cent", '\u{A2}'), ("chi", '\u{3C7}'), ("circ", '\u{2C6}'),
+    ("clubs", '\u{2663}'), ("cong", '\u{2245}'), ("copy", '\u{A9}'), ("crarr", '\u{21B5}'),
+    ("cup", '\u{222A}'), ("curren", '\u{A4}'), ("dArr", '\u{21D3}'), ("dagger", '\u{2020}'),
+    ("darr", '\u{2193}'), ("deg", '\u{B0}'), ("delta", '\u{3B4}'), ("diams", '\u{2666}'),
+    ("divide", '\u{F7}'), ("eacute", '\u{E9}'), ("ecirc", '\u{EA}'), ("egrave", '\u{E8}'),
+    ("empty", '\u{2205}'), ("emsp", '\u{2003}'), ("ensp", '\u{2002}'), ("epsilon", '\u{3B5}'),
+    ("equiv", '\u{2261}'), ("eta", '\u{3B7}'), ("eth", '\u{F0}'), ("euml", '\u{EB}'),
+    ("euro", '\u{20AC}'), ("exist", '\u{2203}'), ("fnof", '\u{192}'), ("forall", '\u{2200}'),
+    ("frac12", '\u{BD}'), ("frac14", '\u{BC}'), ("frac34", '\u{BE}'), ("frasl", '\u{2044}'),
+    ("gamma", '\u{3B3}'), ("ge", '\u{2265}'), ("gt", '\u{3E}'), ("hArr", '\u{21D4}'),
+    ("harr", '\u{2194}'), ("hearts", '\u{2665}'), ("hellip", '\u{2026}'), ("iacute", '\u{ED}'),
+    ("icirc", '\u{EE}'), ("iexcl", '\u{A1}'), ("igrave", '\u{EC}'), ("image", '\u{2111}'),
+    ("infin", '\u{221E}'), ("int", '\u{222B}'), ("iota", '\u{3B9}'), ("iquest", '\u{BF}'),
+    ("isin", '\u{2208}'), ("iuml", '\u{EF}'), ("kappa", '\u{3BA}'), ("lArr", '\u{21D0}'),
+    ("lambda", '\u{3BB}'), ("lang", '\u{2329}'), ("laquo", '\u{AB}'), ("larr", '\u{2190}'),
+    ("lceil", '\u{2308}'), ("ldquo", '\u{201C}'), ("le", '\u{2264}'), ("lfloor", '\u{230A}'),
+    ("lowast", '\u{2217}'), ("loz", '\u{25CA}'), ("lrm", '\u{200E}'), ("lsaquo", '\u{2039}'),
+    ("lsquo", '\u{2018}'), ("lt", '\u{3C}'), ("macr", '\u{AF}'), ("mdash", '\u{2014}'),
+    ("micro", '\u{B5}'), ("middot", '\u{B7}'), ("minus", '\u{2212}'), ("mu", '\u{3BC}'),
+    ("nabla", '\u{2207}'), ("nbsp", '\u{A0}'), ("ndash", '\u{2013}'), ("ne", '\u{2260}'),
+    ("ni", '\u{220B}'), ("not", '\u{AC}'), ("notin", '\u{2209}'), ("nsub", '\u{2284}'),
+    ("ntilde", '\u{F1}'), ("nu", '\u{3BD}'), ("oacute", '\u{F3}'), ("ocirc", '\u{F4}'),
+    ("oelig", '\u{153}'), ("ograve", '\u{F2}'), ("oline", '\u{203E}'), ("omega", '\u{3C9}'),
+    ("omicron", '\u{3BF}'), ("oplus", '\u{2295}'), ("or", '\u{2228}'), ("ordf", '\u{AA}'),
+    ("ordm", '\u{BA}'), ("oslash", '\u{F8}'), ("otilde", '\u{F5}'), ("otimes", '\u{2297}'),
+    ("ouml", '\u{F6}'), ("para", '\u{B6}'), ("part", '\u{2202}'), ("permil", '\u{2030}'),
+    ("perp", '\u{22A5}'), ("phi", '\u{3C6}'), ("pi", '\u{3C0}'), ("piv", '\u{3D6}'),
+    ("plusmn", '\u{B1}'), ("pound", '\u{A3}'), ("prime", '\u{2032}'), ("prod", '\u{220F}'),
+    ("prop", '\u{221D}'), ("psi", '\u{3C8}'), ("quot", '\u{22}'), ("rArr", '\u{21D2}'),
+    ("radic", '\u{221A}'), ("rang", '\u{232A}'), ("raquo", '\u{BB}'), ("rarr", '\u{2192}'),
+    ("rceil", '\u{2309}'), ("rdquo", '\u{201D}'), ("real", '\u{211C}'), ("reg", '\u{AE}'),
+    ("rfloor", '\u{230B}'), ("rho", '\u{3C1}'), ("rlm", '\u{200F}'), ("rsaquo", '\u{203A}'),
+    ("rsquo", '\u{2019}'), ("sbquo", '\u{201A}'), ("scaron", '\u{161}'), ("sdot", '\u{22C5}'),
+    ("sect", '\u{A7}'), ("shy", '\u{AD}'), ("sigma", '\u{3C3}'), ("sigmaf", '\u{3C2}'),
+    ("sim", '\u{223C}'), ("spades", '\u{2660}'), ("sub", '\u{2282}'), ("sube", '\u{2286}'),
+    ("sum", '\u{2211}'), ("sup", '\u{2283}'), ("sup1", '\u{B9}'), ("sup2", '\u{B2}'),
+    ("sup3", '\u{B3}'), ("supe", '\u{2287}'), ("szlig", '\u{DF}'), ("tau", '\u{3C4}'),
+    ("there4", '\u{2234}'), ("theta", '\u{3B8}'), ("thetasym", '\u{3D1}'), ("thinsp", '\u{2009}'),
+    ("thorn", '\u{FE}'), ("tilde", '\u{2DC}'), ("times", '\u{D7}'), ("trade", '\u{2122}'),
+    ("uArr", '\u{21D1}'), ("uacute", '\u{FA}'), ("uarr", '\u{2191}'), ("ucirc", '\u{FB}'),
+    ("ugrave", '\u{F9}'), ("uml", '\u{A8}'), ("upsih", '\u{3D2}'), ("upsilon", '\u{3C5}'),
+    ("uuml", '\u{FC}'), ("weierp", '\u{2118}'), ("xi", '\u{3BE}'), ("yacute", '\u{FD}'),
+    ("yen", '\u{A5}'), ("yuml", '\u{FF}'), ("zeta", '\u{3B6}'), ("zwj", '\u{200D}'),
+    ("zwnj", '\u{200C}'),
+];
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unescape_str(s: &str) -> MStr<'_> {
+        unescape(MStr::new_borrowed(s))
+    }
+
+    #[test]
+    fn escape_unchanged() {
+        assert!(escape_text(MStr::new_borrowed("it's \"quoted\"")).is_borrowed());
+        assert!(escape_attr(MStr::new_borrowed("plain 🐸")).is_borrowed());
+
+        let owned = MStr::new_owned("owned");
+        let ptr = owned.as_ptr();
+        assert_eq!(escape_attr(owned).as_ptr(), ptr);
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(
+            escape_text(MStr::new_borrowed("a < b && c > d \"'")),
+            "a &lt; b &amp;&amp; c &gt; d \"'"
+        );
+        assert_eq!(
+            escape_attr(MStr::new_borrowed("<a href=\"x\" title='y'>")),
+            "&lt;a href=&quot;x&quot; title=&#39;y&#39;&gt;"
+        );
+    }
+
+    #[test]
+    fn entity_table_sorted() {
+        assert!(ENTITIES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn unescape_unchanged() {
+        assert!(unescape_str("no refs 🐸 ; #").is_borrowed());
+    }
+
+    #[test]
+    fn unescape_named() {
+        assert_eq!(
+            unescape_str("&lt;&gt;&amp;&quot;&apos;&nbsp;&copy;&hellip;&euro;&Omega;&thetasym;"),
+            "<>&\"'\u{A0}©…€Ω\u{3D1}"
+        );
+        // names are case sensitive
+        assert_eq!(unescape_str("&Eacute;&eacute;&EACUTE;"), "Éé&EACUTE;");
+    }
+
+    #[test]
+    fn unescape_numeric() {
+        assert_eq!(unescape_str("&#65;&#x42;&#X43;&#0000068;"), "ABCD");
+        assert_eq!(unescape_str("&#x1F438;&#128056;"), "🐸🐸");
+        assert_eq!(
+            unescape_str("&#0;&#xD800;&#x110000;"),
+            "\u{FFFD}\u{FFFD}\u{FFFD}"
+        );
+        // too big for a u32 is out of range like any other number
+        assert_eq!(
+            unescape_str("&#99999999999;&#x100000000;"),
+            "\u{FFFD}\u{FFFD}"
+        );
+        assert_eq!(
+            unescape_str("&#;&#x;&#-1;&#+1;&#1a;"),
+            "&#;&#x;&#-1;&#+1;&#1a;"
+        );
+    }
+
+    #[test]
+    fn unescape_kept() {
+        assert_eq!(unescape_str("&"), "&");
+        assert_eq!(unescape_str("a & b"), "a & b");
+        assert_eq!(unescape_str("&amp"), "&amp");
+        assert_eq!(unescape_str("&&amp;"), "&&");
+        assert_eq!(unescape_str("&bogus;&amp;"), "&bogus;&");
+        assert_eq!(unescape_str("&;"), "&;");
+    }
+
+    #[test]
+    fn roundtrip() {
+        for s in ["", "plain", "<&>\"'", "&amp;", "🐸 & 🐸"] {
+            assert_eq!(unescape(escape_attr(MStr::new_borrowed(s))), s);
+            assert_eq!(unescape(escape_text(MStr::new_borrowed(s))), s);
+        }
+    }
+}
@@ -0,0 +1,339 @@
+//! Escaping and unescaping JSON string contents (the part between the quotes).
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use mstr::escape::json;
+//!
+//! let s = json::unescape(MStr::new_borrowed(r"frog \ud83d\udc38\n")).unwrap();
+//! assert_eq!(s, "frog 🐸\n");
+//!
+//! let s = json::escape(s);
+//! assert_eq!(s, r"frog 🐸\n");
+//! ```
+
+use super::escape_chars;
+use crate::MStr;
+use alloc::string::String;
+use core::fmt::{self, Display, Formatter, Write};
+
+// ===== Escape =====
+
+/// Escapes `s` so it can be put between quotes in a JSON document.
+///
+/// `"` and `\` are escaped with a backslash,
+/// and control characters (`U+0000` to `U+001F`) use their short form (`\n`, `\t`, ...) if there is one,
+/// or `\u00XX` otherwise.
+/// Everything else, including non-ASCII characters, is left as-is.
+///
+/// If nothing needs escaping, `s` is returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::json;
+///
+/// assert_eq!(json::escape(MStr::new_borrowed("a\tb\u{1}")), r"a\tb\u0001");
+/// assert!(json::escape(MStr::new_borrowed("héllo")).is_borrowed());
+/// ```
+#[must_use]
+pub fn escape(s: MStr<'_>) -> MStr<'_> {
+    escape_chars(
+        s,
+        |c| matches!(c, '"' | '\\' | '\0'..='\x1F'),
+        |c, out| match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\x08' => out.push_str("\\b"),
+            '\x0C' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+        },
+    )
+}
+
+// ===== Unescape =====
+
+/// Unescapes the contents of a JSON string.
+///
+/// Supports every JSON escape: `\"`, `\\`, `\/`, `\b`, `\f`, `\n`, `\r`, `\t` and `\uXXXX`.
+/// Surrogate pairs written as two `\u` escapes (`\ud83d\udc38`) are combined into one character.
+///
+/// If `s` has no backslashes, it is returned unchanged.
+///
+/// This only handles escapes.
+/// Characters that JSON doesn't allow unescaped (like raw newlines) are passed through.
+///
+/// # Errors
+///
+/// Returns an error with the byte offset of the offending escape
+/// if an escape is unknown, incomplete, has invalid hex digits, or is an unpaired surrogate.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::json::{self, ErrorKind};
+///
+/// let s = json::unescape(MStr::new_borrowed(r#"\"quoted\" \u00e9"#)).unwrap();
+/// assert_eq!(s, "\"quoted\" é");
+///
+/// let err = json::unescape(MStr::new_borrowed(r"ok \x")).unwrap_err();
+/// assert_eq!(err.offset(), 3);
+/// assert_eq!(err.kind(), ErrorKind::UnknownEscape);
+/// ```
+pub fn unescape(s: MStr<'_>) -> Result<MStr<'_>, UnescapeError> {
+    let Some(first) = s.find('\\') else {
+        return Ok(s);
+    };
+
+    let bytes = s.as_bytes();
+    let mut out = String::with_capacity(s.len());
+    out.push_str(&s[..first]);
+
+    let mut i = first;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            // copy everything up to the next escape at once
+            let end = s[i..].find('\\').map_or(s.len(), |n| i + n);
+            out.push_str(&s[i..end]);
+            i = end;
+            continue;
+        }
+
+        let err = |kind| UnescapeError { offset: i, kind };
+
+        let c = match bytes.get(i + 1) {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\x08',
+            Some(b'f') => '\x0C',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                let (c, len) = unescape_unicode(&bytes[i..]).map_err(err)?;
+                out.push(c);
+                i += len;
+                continue;
+            }
+            Some(_) => return Err(err(ErrorKind::UnknownEscape)),
+            None => return Err(err(ErrorKind::Truncated)),
+        };
+
+        out.push(c);
+        i += 2;
+    }
+
+    Ok(MStr::new_owned(out))
+}
+
+// unescapes a \uXXXX escape (and a second one, for a surrogate pair) at the start of `bytes`
+// returns the character and the number of bytes used
+fn unescape_unicode(bytes: &[u8]) -> Result<(char, usize), ErrorKind> {
+    let high = hex4(&bytes[2..])?;
+
+    if !(0xD800..=0xDFFF).contains(&high) {
+        // can't fail, since it isn't a surrogate
+        return Ok((char::from_u32(high).unwrap(), 6));
+    }
+    if high >= 0xDC00 {
+        return Err(ErrorKind::UnpairedSurrogate);
+    }
+
+    // a high surrogate has to be followed by a \u escape with a low surrogate
+    if bytes.get(6..8) != Some(b"\\u") {
+        return Err(ErrorKind::UnpairedSurrogate);
+    }
+    let low = hex4(&bytes[8..])?;
+    if !(0xDC00..=0xDFFF).contains(&low) {
+        return Err(ErrorKind::UnpairedSurrogate);
+    }
+
+    let c = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+    Ok((char::from_u32(c).unwrap(), 12))
+}
+
+// parses exactly 4 hex digits
+fn hex4(bytes: &[u8]) -> Result<u32, ErrorKind> {
+    let digits = bytes.get(..4).ok_or(ErrorKind::Truncated)?;
+
+    digits.iter().try_fold(0, |n, &b| {
+        let d = (b as char).to_digit(16).ok_or(ErrorKind::InvalidHex)?;
+        Ok(n << 4 | d)
+    })
+}
+
+// ===== Error =====
+
+/// The error returned by [`unescape`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnescapeError {
+    offset: usize,
+    kind: ErrorKind,
+}
+
+/// The kind of an [`UnescapeError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A backslash followed by a character that isn't a JSON escape.
+    UnknownEscape,
+    /// The string ended in the middle of an escape.
+    Truncated,
+    /// A `\u` escape with something other than 4 hex digits.
+    InvalidHex,
+    /// A `\u` escape for a surrogate that isn't part of a valid pair.
+    UnpairedSurrogate,
+}
+
+impl UnescapeError {
+    /// Returns the byte offset of the backslash that starts the invalid escape.
+    #[inline]
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns what was wrong with the escape.
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl Display for UnescapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let msg = match self.kind {
+            ErrorKind::UnknownEscape => "unknown escape",
+            ErrorKind::Truncated => "incomplete escape",
+            ErrorKind::InvalidHex => "invalid hex digits in \\u escape",
+            ErrorKind::UnpairedSurrogate => "unpaired surrogate in \\u escape",
+        };
+        write!(f, "{msg} at offset {}", self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnescapeError {}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unescape_str(s: &str) -> Result<MStr<'_>, UnescapeError> {
+        unescape(MStr::new_borrowed(s))
+    }
+
+    fn err(offset: usize, kind: ErrorKind) -> UnescapeError {
+        UnescapeError { offset, kind }
+    }
+
+    #[test]
+    fn escape_unchanged() {
+        let s = escape(MStr::new_borrowed("plain ünïcödé 🐸 /"));
+        assert!(s.is_borrowed());
+
+        let owned = MStr::new_owned("owned");
+        let ptr = owned.as_ptr();
+        let s = escape(owned);
+        assert!(s.is_owned());
+        assert_eq!(s.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn escape_all() {
+        assert_eq!(
+            escape(MStr::new_borrowed("\"\\\x08\x0C\n\r\t\0\x1F\x7F")),
+            "\\\"\\\\\\b\\f\\n\\r\\t\\u0000\\u001f\x7F"
+        );
+        assert_eq!(escape(MStr::new_borrowed("🐸\"🐸")), "🐸\\\"🐸");
+    }
+
+    #[test]
+    fn unescape_unchanged() {
+        let s = unescape_str("no escapes 🐸").unwrap();
+        assert!(s.is_borrowed());
+
+        let owned = MStr::new_owned("owned");
+        let ptr = owned.as_ptr();
+        assert_eq!(unescape(owned).unwrap().as_ptr(), ptr);
+    }
+
+    #[test]
+    fn unescape_all() {
+        assert_eq!(
+            unescape_str(r#"\"\\\/\b\f\n\r\t"#).unwrap(),
+            "\"\\/\x08\x0C\n\r\t"
+        );
+        assert_eq!(unescape_str(r"\u0041\u00e9\u4E16").unwrap(), "Aé世");
+        assert_eq!(unescape_str(r"a\uD83D\uDC38b").unwrap(), "a🐸b");
+        assert_eq!(unescape_str(r"\udbff\udfff").unwrap(), "\u{10FFFF}");
+        assert_eq!(unescape_str("🐸\\n🐸").unwrap(), "🐸\n🐸");
+    }
+
+    #[test]
+    fn roundtrip() {
+        for s in [
+            "",
+            "plain",
+            "\"\\",
+            "\0\x01\x1F",
+            "tab\there",
+            "🐸 \"frog\"",
+        ] {
+            let escaped = escape(MStr::new_borrowed(s));
+            assert_eq!(unescape(escaped).unwrap(), s);
+        }
+    }
+
+    #[test]
+    fn unescape_errors() {
+        assert_eq!(unescape_str(r"ab\q"), Err(err(2, ErrorKind::UnknownEscape)));
+        assert_eq!(unescape_str("ab\\"), Err(err(2, ErrorKind::Truncated)));
+        assert_eq!(unescape_str(r"\u12"), Err(err(0, ErrorKind::Truncated)));
+        assert_eq!(unescape_str(r"x\u12g4"), Err(err(1, ErrorKind::InvalidHex)));
+        assert_eq!(unescape_str(r"\u+123"), Err(err(0, ErrorKind::InvalidHex)));
+
+        assert_eq!(
+            unescape_str(r"\ud83d"),
+            Err(err(0, ErrorKind::UnpairedSurrogate))
+        );
+        assert_eq!(
+            unescape_str(r"\udc38"),
+            Err(err(0, ErrorKind::UnpairedSurrogate))
+        );
+        assert_eq!(
+            unescape_str(r"\ud83dA"),
+            Err(err(0, ErrorKind::UnpairedSurrogate))
+        );
+        assert_eq!(
+            unescape_str(r"\ud83dx\udc38"),
+            Err(err(0, ErrorKind::UnpairedSurrogate))
+        );
+        assert_eq!(
+            unescape_str(r"\ud83d\udc3"),
+            Err(err(0, ErrorKind::Truncated))
+        );
+        // the offset is in bytes
+        assert_eq!(unescape_str("🐸\\z"), Err(err(4, ErrorKind::UnknownEscape)));
+    }
+
+    #[test]
+    fn error_fmt() {
+        assert_eq!(
+            alloc::format!("{}", err(3, ErrorKind::UnpairedSurrogate)),
+            "unpaired surrogate in \\u escape at offset 3"
+        );
+    }
+}
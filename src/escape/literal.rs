@@ -0,0 +1,603 @@
+//! Escaping and unescaping the contents of Rust and C string literals (the part between the quotes).
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use mstr::escape::literal;
+//!
+//! let s = literal::unescape_rust(MStr::new_borrowed(r"tab\there \x41\u{1F600}")).unwrap();
+//! assert_eq!(s, "tab\there A😀");
+//!
+//! let s = literal::escape_debug_like(s);
+//! assert_eq!(s, r"tab\there A😀");
+//! ```
+
+use crate::MStr;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter, Write};
+use core::ops::Range;
+use core::str;
+
+// ===== Escape =====
+
+/// Escapes `s` the way `str`'s `Debug` implementation does, without the surrounding quotes.
+///
+/// `\t`, `\r`, `\n`, `\\`, `"` and `\0` are escaped with a backslash,
+/// and other non-printable characters (and combining marks) are written as `\u{XXXX}`.
+/// The result is always a valid Rust string literal body, so [`unescape_rust`] reverses it.
+///
+/// If nothing needs escaping, `s` is returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::literal;
+///
+/// assert_eq!(literal::escape_debug_like(MStr::new_borrowed("\"hi\"\n\u{7}")), r#"\"hi\"\n\u{7}"#);
+/// assert!(literal::escape_debug_like(MStr::new_borrowed("it's 🐸")).is_borrowed());
+/// ```
+#[must_use]
+pub fn escape_debug_like(s: MStr<'_>) -> MStr<'_> {
+    // a cheap check first, so strings with nothing to escape don't allocate
+    if !s.chars().any(|c| c != '\'' && c.escape_debug().len() > 1) {
+        return s;
+    }
+
+    let mut out = String::with_capacity(s.len() + 8);
+    let _ = write!(out, "{:?}", s.as_str());
+    if out.len() == s.len() + 2 {
+        // only the quotes were added (`Debug` has escaped less than `char::escape_debug` in some Rust versions)
+        return s;
+    }
+
+    out.pop();
+    out.drain(..1);
+    MStr::new_owned(out)
+}
+
+// ===== Unescape =====
+
+/// Unescapes the contents of a Rust string literal.
+///
+/// Supports `\n`, `\r`, `\t`, `\\`, `\0`, `\'`, `\"`, `\xHH` (up to `\x7F`),
+/// `\u{H...}` (1 to 6 hex digits, with optional `_` separators),
+/// and line continuations (a backslash before a newline removes the newline and any whitespace that follows it).
+///
+/// If `s` has no backslashes, it is returned unchanged.
+///
+/// # Errors
+///
+/// Returns an error with the byte span of the offending escape
+/// if an escape is unknown, incomplete, has invalid hex digits, or is out of range.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::literal::{self, ErrorKind};
+///
+/// let s = literal::unescape_rust(MStr::new_borrowed("one \\\n    line")).unwrap();
+/// assert_eq!(s, "one line");
+///
+/// let err = literal::unescape_rust(MStr::new_borrowed(r"ok \xFF")).unwrap_err();
+/// assert_eq!(err.span(), 3..7);
+/// assert_eq!(err.kind(), ErrorKind::OutOfRange);
+/// ```
+pub fn unescape_rust(s: MStr<'_>) -> Result<MStr<'_>, UnescapeError> {
+    unescape(s, rust_escape)
+}
+
+/// Unescapes the contents of a C string literal.
+///
+/// Supports the simple escapes (`\a`, `\b`, `\f`, `\n`, `\r`, `\t`, `\v`, `\\`, `\'`, `\"` and `\?`),
+/// octal (`\0` to `\377`) and hex (`\xHH...`) byte escapes,
+/// universal character names (`\uXXXX` and `\UXXXXXXXX`),
+/// and line splices (a backslash before a newline removes both).
+///
+/// Byte escapes are raw bytes, so `\xC3\xA9` is `é`.
+/// Consecutive byte escapes have to form valid UTF-8.
+///
+/// If `s` has no backslashes, it is returned unchanged.
+///
+/// # Errors
+///
+/// Returns an error with the byte span of the offending escape
+/// (or of the run of byte escapes, if they aren't valid UTF-8)
+/// if an escape is unknown, incomplete, has invalid digits, or is out of range.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::literal::{self, ErrorKind};
+///
+/// let s = literal::unescape_c(MStr::new_borrowed(r"caf\xC3\xA9\t\101é")).unwrap();
+/// assert_eq!(s, "café\tAé");
+///
+/// let err = literal::unescape_c(MStr::new_borrowed(r"ok \xC3!")).unwrap_err();
+/// assert_eq!(err.span(), 3..7);
+/// assert_eq!(err.kind(), ErrorKind::InvalidUtf8);
+/// ```
+pub fn unescape_c(s: MStr<'_>) -> Result<MStr<'_>, UnescapeError> {
+    unescape(s, c_escape)
+}
+
+// what a single escape turned into
+enum Escaped {
+    Char(char),
+    // a raw byte, which has to be checked for UTF-8 along with its neighbours
+    Byte(u8),
+    // a line continuation
+    Nothing,
+}
+
+// parses the escape whose backslash is at `start`
+// returns what it turned into, and where it ends
+type ParseEscape = fn(&str, usize) -> Result<(Escaped, usize), UnescapeError>;
+
+fn unescape(s: MStr<'_>, parse_escape: ParseEscape) -> Result<MStr<'_>, UnescapeError> {
+    let Some(first) = s.find('\\') else {
+        return Ok(s);
+    };
+
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(s.len());
+    out.extend_from_slice(&bytes[..first]);
+
+    // a run of byte escapes that hasn't been checked yet: (start in `s`, start in `out`)
+    let mut pending = None;
+    let mut i = first;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            check_pending(&out, &mut pending, i)?;
+
+            // copy everything up to the next escape at once
+            let end = s[i..].find('\\').map_or(s.len(), |n| i + n);
+            out.extend_from_slice(&bytes[i..end]);
+            i = end;
+            continue;
+        }
+
+        let (escaped, end) = parse_escape(&s, i)?;
+        match escaped {
+            Escaped::Char(c) => {
+                check_pending(&out, &mut pending, i)?;
+                out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+            Escaped::Byte(b) => {
+                pending.get_or_insert((i, out.len()));
+                out.push(b);
+            }
+            Escaped::Nothing => check_pending(&out, &mut pending, i)?,
+        }
+        i = end;
+    }
+    check_pending(&out, &mut pending, s.len())?;
+
+    // SAFETY: text between escapes is copied whole from a `str`, chars are encoded as UTF-8,
+    // and every run of byte escapes was checked above
+    Ok(MStr::new_owned(unsafe { String::from_utf8_unchecked(out) }))
+}
+
+// checks that the run of byte escapes that ends at `end` is valid UTF-8
+fn check_pending(
+    out: &[u8],
+    pending: &mut Option<(usize, usize)>,
+    end: usize,
+) -> Result<(), UnescapeError> {
+    if let Some((start, out_start)) = pending.take() {
+        if str::from_utf8(&out[out_start..]).is_err() {
+            return Err(UnescapeError {
+                start,
+                end,
+                kind: ErrorKind::InvalidUtf8,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn rust_escape(s: &str, start: usize) -> Result<(Escaped, usize), UnescapeError> {
+    let bytes = s.as_bytes();
+    let err = |end, kind| UnescapeError { start, end, kind };
+
+    let c = match bytes.get(start + 1) {
+        Some(b'n') => '\n',
+        Some(b'r') => '\r',
+        Some(b't') => '\t',
+        Some(b'\\') => '\\',
+        Some(b'0') => '\0',
+        Some(b'\'') => '\'',
+        Some(b'"') => '"',
+        Some(b'x') => {
+            let (n, end) = hex_fixed(s, start, 2)?;
+            if n > 0x7F {
+                return Err(err(end, ErrorKind::OutOfRange));
+            }
+            return Ok((Escaped::Char(char::from(n as u8)), end));
+        }
+        Some(b'u') => return rust_unicode(s, start),
+        Some(b'\n') => return Ok((Escaped::Nothing, skip_whitespace(bytes, start + 2))),
+        Some(b'\r') if bytes.get(start + 2) == Some(&b'\n') => {
+            return Ok((Escaped::Nothing, skip_whitespace(bytes, start + 3)))
+        }
+        Some(_) => return Err(err(char_end(s, start + 1), ErrorKind::UnknownEscape)),
+        None => return Err(err(s.len(), ErrorKind::Truncated)),
+    };
+
+    Ok((Escaped::Char(c), start + 2))
+}
+
+// parses a `\u{...}` escape
+fn rust_unicode(s: &str, start: usize) -> Result<(Escaped, usize), UnescapeError> {
+    let bytes = s.as_bytes();
+    let err = |end, kind| UnescapeError { start, end, kind };
+
+    match bytes.get(start + 2) {
+        Some(b'{') => {}
+        Some(_) => return Err(err(char_end(s, start + 2), ErrorKind::InvalidDigits)),
+        None => return Err(err(s.len(), ErrorKind::Truncated)),
+    }
+
+    let mut value = 0_u32;
+    let mut digits = 0;
+    let mut i = start + 3;
+    loop {
+        match bytes.get(i) {
+            Some(b'}') if digits > 0 => break,
+            // separators are allowed, but not before the first digit
+            Some(b'_') if digits > 0 => {}
+            Some(&b) => match (b as char).to_digit(16) {
+                Some(d) => {
+                    value = value.saturating_mul(16).saturating_add(d);
+                    digits += 1;
+                }
+                None => return Err(err(char_end(s, i), ErrorKind::InvalidDigits)),
+            },
+            None => return Err(err(s.len(), ErrorKind::Truncated)),
+        }
+        i += 1;
+    }
+
+    let end = i + 1;
+    match char::from_u32(value) {
+        Some(c) if digits <= 6 => Ok((Escaped::Char(c), end)),
+        _ => Err(err(end, ErrorKind::OutOfRange)),
+    }
+}
+
+fn c_escape(s: &str, start: usize) -> Result<(Escaped, usize), UnescapeError> {
+    let bytes = s.as_bytes();
+    let err = |end, kind| UnescapeError { start, end, kind };
+
+    let c = match bytes.get(start + 1) {
+        Some(b'a') => '\x07',
+        Some(b'b') => '\x08',
+        Some(b'f') => '\x0C',
+        Some(b'n') => '\n',
+        Some(b'r') => '\r',
+        Some(b't') => '\t',
+        Some(b'v') => '\x0B',
+        Some(b'\\') => '\\',
+        Some(b'\'') => '\'',
+        Some(b'"') => '"',
+        Some(b'?') => '?',
+        Some(b'0'..=b'7') => {
+            // up to 3 octal digits
+            let digits = bytes[start + 1..]
+                .iter()
+                .take(3)
+                .take_while(|b| matches!(b, b'0'..=b'7'))
+                .count();
+            let end = start + 1 + digits;
+            let n = bytes[start + 1..end]
+                .iter()
+                .fold(0_u32, |n, b| n << 3 | u32::from(b - b'0'));
+
+            return match u8::try_from(n) {
+                Ok(b) => Ok((Escaped::Byte(b), end)),
+                Err(_) => Err(err(end, ErrorKind::OutOfRange)),
+            };
+        }
+        Some(b'x') => {
+            // as many hex digits as there are
+            let digits = bytes[start + 2..]
+                .iter()
+                .take_while(|b| b.is_ascii_hexdigit())
+                .count();
+            let end = start + 2 + digits;
+            if digits == 0 {
+                return Err(match bytes.get(end) {
+                    Some(_) => err(char_end(s, end), ErrorKind::InvalidDigits),
+                    None => err(end, ErrorKind::Truncated),
+                });
+            }
+
+            let n = s[start + 2..end].chars().try_fold(0_u8, |n, c| {
+                n.checked_mul(16)?.checked_add(c.to_digit(16)? as u8)
+            });
+            return match n {
+                Some(b) => Ok((Escaped::Byte(b), end)),
+                None => Err(err(end, ErrorKind::OutOfRange)),
+            };
+        }
+        Some(b'u') => return c_unicode(s, start, 4),
+        Some(b'U') => return c_unicode(s, start, 8),
+        Some(b'\n') => return Ok((Escaped::Nothing, start + 2)),
+        Some(b'\r') if bytes.get(start + 2) == Some(&b'\n') => {
+            return Ok((Escaped::Nothing, start + 3))
+        }
+        Some(_) => return Err(err(char_end(s, start + 1), ErrorKind::UnknownEscape)),
+        None => return Err(err(s.len(), ErrorKind::Truncated)),
+    };
+
+    Ok((Escaped::Char(c), start + 2))
+}
+
+// parses a `\uXXXX` or `\UXXXXXXXX` escape
+fn c_unicode(s: &str, start: usize, len: usize) -> Result<(Escaped, usize), UnescapeError> {
+    let (n, end) = hex_fixed(s, start, len)?;
+    match char::from_u32(n) {
+        Some(c) => Ok((Escaped::Char(c), end)),
+        None => Err(UnescapeError {
+            start,
+            end,
+            kind: ErrorKind::OutOfRange,
+        }),
+    }
+}
+
+// parses exactly `len` hex digits after the 2-byte escape prefix at `start`
+// returns the value, and where the digits end
+fn hex_fixed(s: &str, start: usize, len: usize) -> Result<(u32, usize), UnescapeError> {
+    let bytes = s.as_bytes();
+    let err = |end, kind| UnescapeError { start, end, kind };
+
+    let mut n = 0;
+    for i in start + 2..start + 2 + len {
+        match bytes.get(i) {
+            Some(&b) => match (b as char).to_digit(16) {
+                Some(d) => n = n << 4 | d,
+                None => return Err(err(char_end(s, i), ErrorKind::InvalidDigits)),
+            },
+            None => return Err(err(s.len(), ErrorKind::Truncated)),
+        }
+    }
+    Ok((n, start + 2 + len))
+}
+
+// the end of the char that starts at `i`
+#[inline]
+fn char_end(s: &str, i: usize) -> usize {
+    i + s[i..].chars().next().map_or(0, char::len_utf8)
+}
+
+// skips the whitespace after a line continuation, like rustc does
+#[inline]
+fn skip_whitespace(bytes: &[u8], mut i: usize) -> usize {
+    while matches!(bytes.get(i), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+        i += 1;
+    }
+    i
+}
+
+// ===== Error =====
+
+/// The error returned by [`unescape_rust`] and [`unescape_c`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnescapeError {
+    start: usize,
+    end: usize,
+    kind: ErrorKind,
+}
+
+/// The kind of an [`UnescapeError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A backslash followed by a character that isn't an escape.
+    UnknownEscape,
+    /// The string ended in the middle of an escape.
+    Truncated,
+    /// An escape with a character where a digit should be.
+    InvalidDigits,
+    /// An escape whose value is too large, or isn't a valid `char`.
+    OutOfRange,
+    /// A run of C byte escapes that isn't valid UTF-8.
+    InvalidUtf8,
+}
+
+impl UnescapeError {
+    /// Returns the byte span of the invalid escape, starting at its backslash.
+    ///
+    /// For [`ErrorKind::InvalidUtf8`], this spans the whole run of byte escapes.
+    #[inline]
+    #[must_use]
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns what was wrong with the escape.
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl Display for UnescapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let msg = match self.kind {
+            ErrorKind::UnknownEscape => "unknown escape",
+            ErrorKind::Truncated => "incomplete escape",
+            ErrorKind::InvalidDigits => "invalid digits in escape",
+            ErrorKind::OutOfRange => "escape out of range",
+            ErrorKind::InvalidUtf8 => "byte escapes are not valid UTF-8",
+        };
+        write!(f, "{msg} at {}..{}", self.start, self.end)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnescapeError {}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rust(s: &str) -> Result<MStr<'_>, UnescapeError> {
+        unescape_rust(MStr::new_borrowed(s))
+    }
+
+    fn c(s: &str) -> Result<MStr<'_>, UnescapeError> {
+        unescape_c(MStr::new_borrowed(s))
+    }
+
+    fn err(span: Range<usize>, kind: ErrorKind) -> UnescapeError {
+        UnescapeError {
+            start: span.start,
+            end: span.end,
+            kind,
+        }
+    }
+
+    #[test]
+    fn unescape_unchanged() {
+        assert!(rust("no escapes 🐸").unwrap().is_borrowed());
+        assert!(c("no escapes 🐸").unwrap().is_borrowed());
+
+        let owned = MStr::new_owned("owned");
+        let ptr = owned.as_ptr();
+        assert_eq!(unescape_rust(owned).unwrap().as_ptr(), ptr);
+    }
+
+    #[test]
+    fn rust_escapes() {
+        assert_eq!(rust(r#"\n\r\t\\\0\'\""#).unwrap(), "\n\r\t\\\0'\"");
+        assert_eq!(rust(r"\x41\x7f\x7F").unwrap(), "A\x7F\x7F");
+        assert_eq!(
+            rust(r"\u{41}\u{e9}\u{1F600}\u{10_FFFF}\u{0000_41}").unwrap(),
+            "Aé😀\u{10FFFF}A"
+        );
+        assert_eq!(rust("🐸\\n🐸").unwrap(), "🐸\n🐸");
+    }
+
+    #[test]
+    fn rust_line_continuation() {
+        assert_eq!(rust("a\\\n   \t b").unwrap(), "ab");
+        assert_eq!(rust("a\\\r\n\n  b").unwrap(), "ab");
+        assert_eq!(rust("a\\\n").unwrap(), "a");
+    }
+
+    #[test]
+    fn rust_errors() {
+        assert_eq!(rust(r"ab\q"), Err(err(2..4, ErrorKind::UnknownEscape)));
+        assert_eq!(rust("ab\\🐸"), Err(err(2..7, ErrorKind::UnknownEscape)));
+        assert_eq!(rust(r"\a"), Err(err(0..2, ErrorKind::UnknownEscape)));
+        assert_eq!(rust("ab\\"), Err(err(2..3, ErrorKind::Truncated)));
+
+        assert_eq!(rust(r"\x4"), Err(err(0..3, ErrorKind::Truncated)));
+        assert_eq!(rust(r"\x4g"), Err(err(0..4, ErrorKind::InvalidDigits)));
+        assert_eq!(rust(r"\x80"), Err(err(0..4, ErrorKind::OutOfRange)));
+
+        assert_eq!(rust(r"\u41"), Err(err(0..3, ErrorKind::InvalidDigits)));
+        assert_eq!(rust(r"\u{41"), Err(err(0..5, ErrorKind::Truncated)));
+        assert_eq!(rust(r"\u{}"), Err(err(0..4, ErrorKind::InvalidDigits)));
+        assert_eq!(rust(r"\u{_1}"), Err(err(0..4, ErrorKind::InvalidDigits)));
+        assert_eq!(rust(r"\u{4g}"), Err(err(0..5, ErrorKind::InvalidDigits)));
+        assert_eq!(rust(r"\u{D800}"), Err(err(0..8, ErrorKind::OutOfRange)));
+        assert_eq!(rust(r"\u{110000}"), Err(err(0..10, ErrorKind::OutOfRange)));
+        assert_eq!(rust(r"\u{0000041}"), Err(err(0..11, ErrorKind::OutOfRange)));
+        assert_eq!(
+            rust(r"\u{FFFFFFFFFFFF}"),
+            Err(err(0..16, ErrorKind::OutOfRange))
+        );
+    }
+
+    #[test]
+    fn c_escapes() {
+        assert_eq!(
+            c(r#"\a\b\f\n\r\t\v\\\'\"\?"#).unwrap(),
+            "\x07\x08\x0C\n\r\t\x0B\\'\"?"
+        );
+        assert_eq!(c(r"\0\101\1011\7").unwrap(), "\0AA1\x07");
+        // hex escapes use every hex digit that follows
+        assert_eq!(c(r"\x41\x041\x0000041 \x4g").unwrap(), "AAA \x04g");
+        assert_eq!(c(r"é\U0001F600").unwrap(), "é😀");
+        assert_eq!(c("a\\\nb\\\r\n  c").unwrap(), "ab  c");
+    }
+
+    #[test]
+    fn c_byte_escapes() {
+        assert_eq!(c(r"\xC3\xA9").unwrap(), "é");
+        assert_eq!(c(r"\360\237\230\200").unwrap(), "😀");
+        assert_eq!(c(r"\xF0\x9F\x98\x80 \xC3\xA9").unwrap(), "😀 é");
+
+        // a run of byte escapes that isn't UTF-8 on its own
+        assert_eq!(c(r"ab\xC3"), Err(err(2..6, ErrorKind::InvalidUtf8)));
+        assert_eq!(c(r"\xC3\n\xA9"), Err(err(0..4, ErrorKind::InvalidUtf8)));
+        assert_eq!(c(r"\xC3\xA9\xFFx"), Err(err(0..12, ErrorKind::InvalidUtf8)));
+        // a line splice ends a run too
+        assert_eq!(c("\\xC3\\\n\\xA9"), Err(err(0..4, ErrorKind::InvalidUtf8)));
+    }
+
+    #[test]
+    fn c_errors() {
+        assert_eq!(c(r"ab\q"), Err(err(2..4, ErrorKind::UnknownEscape)));
+        assert_eq!(c("ab\\"), Err(err(2..3, ErrorKind::Truncated)));
+        assert_eq!(c(r"\400"), Err(err(0..4, ErrorKind::OutOfRange)));
+        assert_eq!(c(r"\x"), Err(err(0..2, ErrorKind::Truncated)));
+        assert_eq!(c(r"\xg"), Err(err(0..3, ErrorKind::InvalidDigits)));
+        assert_eq!(c(r"\x100"), Err(err(0..5, ErrorKind::OutOfRange)));
+        assert_eq!(c(r"\u00e"), Err(err(0..5, ErrorKind::Truncated)));
+        assert_eq!(c(r"\u00eg"), Err(err(0..6, ErrorKind::InvalidDigits)));
+        assert_eq!(c(r"\uD800"), Err(err(0..6, ErrorKind::OutOfRange)));
+        assert_eq!(c(r"\U00110000"), Err(err(0..10, ErrorKind::OutOfRange)));
+    }
+
+    #[test]
+    fn escape_debug() {
+        assert!(escape_debug_like(MStr::new_borrowed("plain 'ünïcödé' 🐸")).is_borrowed());
+        assert_eq!(
+            escape_debug_like(MStr::new_borrowed("e\u{301}")),
+            r"e\u{301}"
+        );
+
+        assert_eq!(
+            escape_debug_like(MStr::new_borrowed("\"\\\t\r\n\0\x7F\u{200B}")),
+            r#"\"\\\t\r\n\0\u{7f}\u{200b}"#
+        );
+
+        let owned = MStr::new_owned("owned");
+        let ptr = owned.as_ptr();
+        assert_eq!(escape_debug_like(owned).as_ptr(), ptr);
+    }
+
+    #[test]
+    fn roundtrip() {
+        for s in [
+            "",
+            "plain",
+            "\"quoted\" 'single'",
+            "\0\x01\x1F\x7F",
+            "tab\there\r\n",
+            "🐸 \u{301}\u{200B}\u{FEFF}",
+        ] {
+            let escaped = escape_debug_like(MStr::new_borrowed(s));
+            assert_eq!(unescape_rust(escaped).unwrap(), s);
+        }
+    }
+
+    #[test]
+    fn error_fmt() {
+        assert_eq!(
+            alloc::format!("{}", err(3..7, ErrorKind::OutOfRange)),
+            "escape out of range at 3..7"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Escaping and unescaping strings for various formats.
+//!
+//! The escaping and unescaping functions take an `MStr<'a>` and return an `MStr<'a>`.
+//! If nothing needs to change, the input is returned untouched,
+//! so borrowed strings stay borrowed and owned strings keep their allocation.
+//! A new string is only allocated when something is actually escaped or unescaped.
+//!
+//! - [`form_urlencoded`]: `application/x-www-form-urlencoded` query strings and form bodies.
+//! - [`json`]: JSON string contents.
+//! - [`html`]: HTML text and attribute values.
+//! - [`literal`]: Rust and C string literal contents.
+//! - [`percent`]: percent-encoding (URL encoding).
+//! - [`xml`]: XML text and attribute values.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use mstr::escape::json;
+//!
+//! let s = json::escape(MStr::new_borrowed("no quotes here"));
+//! assert!(s.is_borrowed());
+//!
+//! let s = json::escape(MStr::new_borrowed("say \"hi\""));
+//! assert_eq!(s, r#"say \"hi\""#);
+//! ```
+
+pub mod form_urlencoded;
+pub mod html;
+pub mod json;
+pub mod literal;
+pub mod percent;
+pub mod xml;
+
+use crate::MStr;
+use alloc::string::String;
+
+// escapes every char that `needs_escape` matches, by calling `escape`
+// if nothing matches, `s` is returned unchanged
+#[inline]
+fn escape_chars<'a>(
+    s: MStr<'a>,
+    needs_escape: impl Fn(char) -> bool,
+    mut escape: impl FnMut(char, &mut String),
+) -> MStr<'a> {
+    let Some(first) = s.find(&needs_escape) else {
+        return s;
+    };
+
+    // most strings only have a few characters to escape
+    let mut out = String::with_capacity(s.len() + s.len() / 8 + 8);
+    out.push_str(&s[..first]);
+
+    for c in s[first..].chars() {
+        if needs_escape(c) {
+            escape(c, &mut out);
+        } else {
+            out.push(c);
+        }
+    }
+
+    MStr::new_owned(out)
+}
+
+// the longest `&...;` reference that is looked for
+// (longer than any named entity or in-range numeric reference, with room for leading zeros)
+const MAX_REF_LEN: usize = 32;
+
+// calls `resolve` for every `&` in `s`, with its offset,
+// and the text between it and the next `;` (or None if there is no `;` close enough)
+// `resolve` either pushes the replacement and returns true, or returns false to keep the `&` as-is
+// if there is no `&`, `s` is returned unchanged
+#[inline]
+fn unescape_refs<'a, E>(
+    s: MStr<'a>,
+    mut resolve: impl FnMut(usize, Option<&str>, &mut String) -> Result<bool, E>,
+) -> Result<MStr<'a>, E> {
+    let Some(first) = s.find('&') else {
+        return Ok(s);
+    };
+
+    let mut out = String::with_capacity(s.len());
+    out.push_str(&s[..first]);
+
+    let mut i = first;
+    while let Some(n) = s[i..].find('&') {
+        let amp = i + n;
+        out.push_str(&s[i..amp]);
+
+        let rest = &s.as_bytes()[amp + 1..];
+        let semi = rest.iter().take(MAX_REF_LEN).position(|&b| b == b';');
+        // `;` is ascii, so this is always on a char boundary
+        let body = semi.map(|semi| &s[amp + 1..amp + 1 + semi]);
+
+        if resolve(amp, body, &mut out)? {
+            // skip `&`, the body, and `;`
+            i = amp + 1 + body.map_or(0, |b| b.len() + 1);
+        } else {
+            out.push('&');
+            i = amp + 1;
+        }
+    }
+    out.push_str(&s[i..]);
+
+    Ok(MStr::new_owned(out))
+}
+
+// parses the body of a numeric character reference (`#123` or `#x7B`, without the `&` and `;`)
+// returns None if it isn't one. numbers that don't fit in a u32 become u32::MAX,
+// so they're out of range like any other number above char::MAX
+fn parse_char_ref(body: &str) -> Option<u32> {
+    let digits = body.strip_prefix('#')?;
+    let (digits, radix) = match digits.strip_prefix(&['x', 'X'][..]) {
+        Some(hex) => (hex, 16),
+        None => (digits, 10),
+    };
+
+    // from_str_radix accepts a leading +
+    if digits.is_empty() || !digits.bytes().all(|b| (b as char).is_digit(radix)) {
+        return None;
+    }
+    // the digits are all valid, so this can only fail by overflowing
+    Some(u32::from_str_radix(digits, radix).unwrap_or(u32::MAX))
+}
@@ -0,0 +1,497 @@
+//! Percent-encoding (URL encoding).
+//!
+//! [`encode`] takes an [`AsciiSet`] of the ASCII bytes to encode.
+//! Non-ASCII bytes are always encoded.
+//! The predefined sets follow the [WHATWG URL standard](https://url.spec.whatwg.org/#percent-encoded-bytes).
+//!
+//! See also [`form_urlencoded`](super::form_urlencoded), for query strings and form bodies.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use mstr::escape::percent::{self, COMPONENT};
+//!
+//! let s = percent::encode(MStr::new_borrowed("a/b c"), &COMPONENT);
+//! assert_eq!(s, "a%2Fb%20c");
+//!
+//! let s = percent::decode(s).unwrap();
+//! assert_eq!(s, "a/b c");
+//!
+//! // nothing to decode, so nothing is allocated
+//! assert!(percent::decode(MStr::new_borrowed("plain")).unwrap().is_borrowed());
+//! ```
+
+use crate::MStr;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+use core::str;
+
+// ===== AsciiSet =====
+
+/// A set of ASCII bytes, for choosing which bytes [`encode`] encodes.
+///
+/// Sets are built in `const` contexts by adding to, or removing from, an existing set.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::percent::{self, AsciiSet, CONTROLS};
+///
+/// const SPACES_AND_SLASHES: AsciiSet = CONTROLS.add(b' ').add(b'/');
+///
+/// let s = percent::encode(MStr::new_borrowed("a/b c?"), &SPACES_AND_SLASHES);
+/// assert_eq!(s, "a%2Fb%20c?");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiSet {
+    // one bit per ascii byte
+    mask: u128,
+}
+
+impl AsciiSet {
+    /// The empty set.
+    pub const EMPTY: AsciiSet = AsciiSet { mask: 0 };
+
+    /// Returns a copy of this set with `byte` added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte` isn't ASCII.
+    #[must_use]
+    pub const fn add(self, byte: u8) -> AsciiSet {
+        assert!(byte.is_ascii(), "AsciiSet can only contain ASCII bytes");
+        AsciiSet {
+            mask: self.mask | 1 << byte,
+        }
+    }
+
+    /// Returns a copy of this set with `byte` removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte` isn't ASCII.
+    #[must_use]
+    pub const fn remove(self, byte: u8) -> AsciiSet {
+        assert!(byte.is_ascii(), "AsciiSet can only contain ASCII bytes");
+        AsciiSet {
+            mask: self.mask & !(1 << byte),
+        }
+    }
+
+    /// Returns the union of this set and `other`.
+    #[must_use]
+    pub const fn union(self, other: AsciiSet) -> AsciiSet {
+        AsciiSet {
+            mask: self.mask | other.mask,
+        }
+    }
+
+    /// Checks if `byte` is in this set.
+    ///
+    /// Non-ASCII bytes are never in the set.
+    #[inline]
+    #[must_use]
+    pub const fn contains(&self, byte: u8) -> bool {
+        byte.is_ascii() && self.mask & 1 << byte != 0
+    }
+
+    // non-ascii bytes are always encoded
+    #[inline]
+    const fn should_encode(&self, byte: u8) -> bool {
+        !byte.is_ascii() || self.contains(byte)
+    }
+}
+
+/// The C0 control characters (`0x00..=0x1F`) and DEL (`0x7F`).
+pub const CONTROLS: AsciiSet = AsciiSet {
+    mask: 0xFFFF_FFFF | 1 << 0x7F,
+};
+
+/// [`CONTROLS`], and space, `"`, `<`, `>` and `` ` ``.
+///
+/// The WHATWG fragment percent-encode set.
+pub const FRAGMENT: AsciiSet = CONTROLS.add(b' ').add(b'"').add(b'<').add(b'>').add(b'`');
+
+/// [`CONTROLS`], and space, `"`, `#`, `<` and `>`.
+///
+/// The WHATWG query percent-encode set.
+pub const QUERY: AsciiSet = CONTROLS.add(b' ').add(b'"').add(b'#').add(b'<').add(b'>');
+
+/// [`QUERY`], and `?`, `` ` ``, `{` and `}`.
+///
+/// The WHATWG path percent-encode set.
+pub const PATH: AsciiSet = QUERY.add(b'?').add(b'`').add(b'{').add(b'}');
+
+/// [`PATH`], and `/`, `:`, `;`, `=`, `@`, `[`, `\`, `]`, `^` and `|`.
+///
+/// The WHATWG userinfo percent-encode set.
+pub const USERINFO: AsciiSet = PATH
+    .add(b'/')
+    .add(b':')
+    .add(b';')
+    .add(b'=')
+    .add(b'@')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'|');
+
+/// [`USERINFO`], and `$`, `%`, `&`, `+` and `,`.
+///
+/// The WHATWG component percent-encode set, for encoding a single URL component.
+pub const COMPONENT: AsciiSet = USERINFO.add(b'$').add(b'%').add(b'&').add(b'+').add(b',');
+
+/// Every ASCII byte except letters and digits.
+pub const NON_ALPHANUMERIC: AsciiSet = AsciiSet {
+    mask: !(0x3FF << b'0' | 0x3FF_FFFF << b'A' | 0x3FF_FFFF << b'a'),
+};
+
+// ===== Encode =====
+
+/// Percent-encodes every byte of `s` that is in `set`, and every non-ASCII byte.
+///
+/// If nothing needs encoding, `s` is returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::percent::{self, PATH};
+///
+/// assert_eq!(percent::encode(MStr::new_borrowed("/frog 🐸"), &PATH), "/frog%20%F0%9F%90%B8");
+/// assert!(percent::encode(MStr::new_borrowed("/frog"), &PATH).is_borrowed());
+/// ```
+#[must_use]
+pub fn encode<'a>(s: MStr<'a>, set: &AsciiSet) -> MStr<'a> {
+    let Some(first) = s.bytes().position(|b| set.should_encode(b)) else {
+        return s;
+    };
+
+    let mut out = String::with_capacity(s.len() + s.len() / 4 + 8);
+    out.push_str(&s[..first]);
+    encode_into(&s.as_bytes()[first..], set, |b| b, &mut out);
+
+    MStr::new_owned(out)
+}
+
+// encodes `bytes` into `out`, passing each byte that isn't encoded through `map` first
+#[inline]
+pub(super) fn encode_into(bytes: &[u8], set: &AsciiSet, map: impl Fn(u8) -> u8, out: &mut String) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    for &b in bytes {
+        if set.should_encode(b) {
+            out.push('%');
+            out.push(HEX[usize::from(b >> 4)] as char);
+            out.push(HEX[usize::from(b & 0xF)] as char);
+        } else {
+            out.push(map(b) as char);
+        }
+    }
+}
+
+// ===== Decode =====
+
+/// Decodes percent-encoded bytes (`%XX`) in `s`.
+///
+/// If `s` has no `%`, it is returned unchanged.
+///
+/// # Errors
+///
+/// Returns an error with the byte offset (in `s`) of the problem
+/// if a `%` isn't followed by two hex digits,
+/// or if the decoded bytes aren't valid UTF-8.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::percent::{self, ErrorKind};
+///
+/// assert_eq!(percent::decode(MStr::new_borrowed("caf%C3%A9")).unwrap(), "café");
+///
+/// let err = percent::decode(MStr::new_borrowed("100%")).unwrap_err();
+/// assert_eq!(err.offset(), 3);
+/// assert_eq!(err.kind(), ErrorKind::InvalidEscape);
+///
+/// let err = percent::decode(MStr::new_borrowed("ok%FF")).unwrap_err();
+/// assert_eq!(err.offset(), 2);
+/// assert_eq!(err.kind(), ErrorKind::InvalidUtf8);
+/// ```
+pub fn decode(s: MStr<'_>) -> Result<MStr<'_>, DecodeError> {
+    if !s.contains('%') {
+        return Ok(s);
+    }
+
+    let bytes = decode_bytes(&s, false, true).map_err(|offset| DecodeError {
+        offset,
+        kind: ErrorKind::InvalidEscape,
+    })?;
+
+    match String::from_utf8(bytes) {
+        Ok(out) => Ok(MStr::new_owned(out)),
+        Err(e) => Err(DecodeError {
+            offset: input_offset(&s, e.utf8_error().valid_up_to()),
+            kind: ErrorKind::InvalidUtf8,
+        }),
+    }
+}
+
+/// Decodes percent-encoded bytes (`%XX`) in `s`, never failing.
+///
+/// A `%` that isn't followed by two hex digits is kept as-is (like browsers do),
+/// and invalid UTF-8 is replaced with `U+FFFD`.
+///
+/// If `s` has no `%`, it is returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::percent;
+///
+/// assert_eq!(percent::decode_lossy(MStr::new_borrowed("100% %41%FF")), "100% A\u{FFFD}");
+/// ```
+#[must_use]
+pub fn decode_lossy(s: MStr<'_>) -> MStr<'_> {
+    decode_lossy_impl(s, false)
+}
+
+pub(super) fn decode_lossy_impl(s: MStr<'_>, plus_as_space: bool) -> MStr<'_> {
+    if !(s.contains('%') || plus_as_space && s.contains('+')) {
+        return s;
+    }
+
+    // can't fail when not strict
+    let bytes = decode_bytes(&s, plus_as_space, false).unwrap_or_default();
+
+    match String::from_utf8(bytes) {
+        Ok(out) => MStr::new_owned(out),
+        Err(e) => MStr::new_owned(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+    }
+}
+
+#[inline]
+fn hex_digit(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|d| d as u8)
+}
+
+// the value of a valid `%XX` escape at the start of `bytes`
+#[inline]
+fn escape_at(bytes: &[u8]) -> Option<u8> {
+    match bytes {
+        [b'%', hi, lo, ..] => Some(hex_digit(*hi)? << 4 | hex_digit(*lo)?),
+        _ => None,
+    }
+}
+
+// decodes `%XX` (and `+`, if `plus_as_space`) into bytes
+// if `strict`, an invalid escape returns its offset, otherwise it is kept as-is
+fn decode_bytes(s: &str, plus_as_space: bool, strict: bool) -> Result<Vec<u8>, usize> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => match escape_at(&bytes[i..]) {
+                Some(b) => {
+                    out.push(b);
+                    i += 3;
+                    continue;
+                }
+                None if strict => return Err(i),
+                None => out.push(b'%'),
+            },
+            b'+' if plus_as_space => out.push(b' '),
+            b => out.push(b),
+        }
+        i += 1;
+    }
+
+    Ok(out)
+}
+
+// maps an offset in the decoded bytes back to an offset in `s`
+fn input_offset(s: &str, decoded_offset: usize) -> usize {
+    let bytes = s.as_bytes();
+
+    let mut i = 0;
+    for _ in 0..decoded_offset {
+        i += if escape_at(&bytes[i..]).is_some() {
+            3
+        } else {
+            1
+        };
+    }
+    i
+}
+
+// ===== Error =====
+
+/// The error returned by [`decode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    offset: usize,
+    kind: ErrorKind,
+}
+
+/// The kind of a [`DecodeError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A `%` that isn't followed by two hex digits.
+    InvalidEscape,
+    /// The decoded bytes aren't valid UTF-8.
+    InvalidUtf8,
+}
+
+impl DecodeError {
+    /// Returns the byte offset in the input of the invalid escape,
+    /// or of the first byte of the invalid UTF-8 sequence.
+    #[inline]
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns what was wrong with the input.
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let msg = match self.kind {
+            ErrorKind::InvalidEscape => "invalid percent escape",
+            ErrorKind::InvalidUtf8 => "percent-decoded bytes are not valid UTF-8",
+        };
+        write!(f, "{msg} at offset {}", self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_str(s: &str) -> Result<MStr<'_>, DecodeError> {
+        decode(MStr::new_borrowed(s))
+    }
+
+    fn err(offset: usize, kind: ErrorKind) -> DecodeError {
+        DecodeError { offset, kind }
+    }
+
+    #[test]
+    fn sets() {
+        assert!(CONTROLS.contains(0) && CONTROLS.contains(0x1F) && CONTROLS.contains(0x7F));
+        assert!(!CONTROLS.contains(b' ') && !CONTROLS.contains(0x80));
+
+        assert!(NON_ALPHANUMERIC.contains(b'-') && NON_ALPHANUMERIC.contains(b'~'));
+        assert!(NON_ALPHANUMERIC.contains(b'/') && NON_ALPHANUMERIC.contains(b':'));
+        assert!(NON_ALPHANUMERIC.contains(b'@') && NON_ALPHANUMERIC.contains(b'['));
+        assert!(NON_ALPHANUMERIC.contains(b'`') && NON_ALPHANUMERIC.contains(b'{'));
+        assert!((b'0'..=b'9')
+            .chain(b'A'..=b'Z')
+            .chain(b'a'..=b'z')
+            .all(|b| !NON_ALPHANUMERIC.contains(b)));
+
+        assert!(COMPONENT.contains(b'%') && !PATH.contains(b'%'));
+        assert!(PATH.contains(b'?') && !QUERY.contains(b'?'));
+        assert!(!COMPONENT.contains(b'*') && !COMPONENT.contains(b'~'));
+
+        let set = AsciiSet::EMPTY.add(b'a').add(b'b').remove(b'a');
+        assert!(!set.contains(b'a') && set.contains(b'b'));
+        assert_eq!(
+            set.union(AsciiSet::EMPTY.add(b'a')),
+            AsciiSet::EMPTY.add(b'b').add(b'a')
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_non_ascii() {
+        let _ = AsciiSet::EMPTY.add(0x80);
+    }
+
+    #[test]
+    fn encode_unchanged() {
+        assert!(encode(
+            MStr::new_borrowed("a-b_c.d~e"),
+            &NON_ALPHANUMERIC
+                .remove(b'-')
+                .remove(b'_')
+                .remove(b'.')
+                .remove(b'~')
+        )
+        .is_borrowed());
+
+        let owned = MStr::new_owned("abc");
+        let ptr = owned.as_ptr();
+        assert_eq!(encode(owned, &COMPONENT).as_ptr(), ptr);
+    }
+
+    #[test]
+    fn encode_sets() {
+        let s = "a b/c?d#e&f=g%h\u{7F}é";
+        let enc = |set| encode(MStr::new_borrowed(s), set);
+
+        assert_eq!(enc(&CONTROLS), "a b/c?d#e&f=g%h%7F%C3%A9");
+        assert_eq!(enc(&QUERY), "a%20b/c?d%23e&f=g%h%7F%C3%A9");
+        assert_eq!(enc(&PATH), "a%20b/c%3Fd%23e&f=g%h%7F%C3%A9");
+        assert_eq!(enc(&COMPONENT), "a%20b%2Fc%3Fd%23e%26f%3Dg%25h%7F%C3%A9");
+        assert_eq!(enc(&AsciiSet::EMPTY), "a b/c?d#e&f=g%h\u{7F}%C3%A9");
+    }
+
+    #[test]
+    fn decode_ok() {
+        assert!(decode_str("no escapes + here").unwrap().is_borrowed());
+        assert_eq!(decode_str("%41%62%2f%2F%25").unwrap(), "Ab//%");
+        assert_eq!(decode_str("%F0%9F%90%B8!").unwrap(), "🐸!");
+        assert_eq!(decode_str("a+b").unwrap(), "a+b");
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert_eq!(decode_str("%"), Err(err(0, ErrorKind::InvalidEscape)));
+        assert_eq!(decode_str("ab%4"), Err(err(2, ErrorKind::InvalidEscape)));
+        assert_eq!(decode_str("%41%G1"), Err(err(3, ErrorKind::InvalidEscape)));
+        assert_eq!(decode_str("%41%+1"), Err(err(3, ErrorKind::InvalidEscape)));
+
+        assert_eq!(decode_str("%FF"), Err(err(0, ErrorKind::InvalidUtf8)));
+        assert_eq!(
+            decode_str("a%41é%F0%9F%90"),
+            Err(err(6, ErrorKind::InvalidUtf8))
+        );
+    }
+
+    #[test]
+    fn decode_lossy_keeps_going() {
+        assert!(decode_lossy(MStr::new_borrowed("plain")).is_borrowed());
+        assert_eq!(decode_lossy(MStr::new_borrowed("%%41%4")), "%A%4");
+        assert_eq!(decode_lossy(MStr::new_borrowed("%FFa")), "\u{FFFD}a");
+    }
+
+    #[test]
+    fn roundtrip() {
+        for s in ["", "plain", "a b/c?d#e&f=g%h", "🐸 frogs 🐸", "\0\x7F"] {
+            let encoded = encode(MStr::new_borrowed(s), &NON_ALPHANUMERIC);
+            assert!(encoded
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'%'));
+            assert_eq!(decode(encoded).unwrap(), s);
+        }
+    }
+}
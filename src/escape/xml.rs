@@ -0,0 +1,231 @@
+//! Escaping and unescaping XML.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use mstr::escape::xml;
+//!
+//! assert_eq!(xml::escape(MStr::new_borrowed("<a b=\"c\">")), "&lt;a b=&quot;c&quot;&gt;");
+//! assert_eq!(xml::unescape(MStr::new_borrowed("&lt;&#x1F438;&gt;")).unwrap(), "<🐸>");
+//! ```
+
+use super::{escape_chars, parse_char_ref, unescape_refs};
+use crate::MStr;
+use core::fmt::{self, Display, Formatter};
+
+// ===== Escape =====
+
+/// Escapes `s` for use as XML text content or a quoted attribute value.
+///
+/// `&`, `<`, `>`, `"` and `'` are replaced with
+/// `&amp;`, `&lt;`, `&gt;`, `&quot;` and `&apos;`.
+///
+/// Characters that XML doesn't allow at all (like most control characters) are left as-is.
+///
+/// If nothing needs escaping, `s` is returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::xml;
+///
+/// assert_eq!(xml::escape(MStr::new_borrowed("Tom & Jerry's")), "Tom &amp; Jerry&apos;s");
+/// assert!(xml::escape(MStr::new_borrowed("plain")).is_borrowed());
+/// ```
+#[must_use]
+pub fn escape(s: MStr<'_>) -> MStr<'_> {
+    escape_chars(
+        s,
+        |c| matches!(c, '&' | '<' | '>' | '"' | '\''),
+        |c, out| {
+            out.push_str(match c {
+                '&' => "&amp;",
+                '<' => "&lt;",
+                '>' => "&gt;",
+                '"' => "&quot;",
+                '\'' => "&apos;",
+                _ => unreachable!(),
+            })
+        },
+    )
+}
+
+// ===== Unescape =====
+
+/// Unescapes XML text or attribute values.
+///
+/// Decodes the 5 predefined entities (`&amp;`, `&lt;`, `&gt;`, `&quot;` and `&apos;`),
+/// and numeric character references (`&#233;` and `&#xE9;`).
+///
+/// If `s` has no `&`, it is returned unchanged.
+///
+/// # Errors
+///
+/// Unlike HTML, XML doesn't allow a bare `&`.
+/// Returns an error with the byte offset of the `&` if a reference has no `;`,
+/// is an unknown entity (XML documents can declare their own, but those aren't supported),
+/// or refers to a code point that isn't a valid character.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::escape::xml::{self, ErrorKind};
+///
+/// assert_eq!(xml::unescape(MStr::new_borrowed("&quot;hi&quot;")).unwrap(), "\"hi\"");
+///
+/// let err = xml::unescape(MStr::new_borrowed("a &nbsp; b")).unwrap_err();
+/// assert_eq!(err.offset(), 2);
+/// assert_eq!(err.kind(), ErrorKind::UnknownEntity);
+/// ```
+pub fn unescape(s: MStr<'_>) -> Result<MStr<'_>, UnescapeError> {
+    unescape_refs(s, |offset, body, out| {
+        let err = |kind| UnescapeError { offset, kind };
+        let body = body.ok_or(err(ErrorKind::Unterminated))?;
+
+        let c = match body {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ if body.starts_with('#') => parse_char_ref(body)
+                .and_then(char::from_u32)
+                .filter(|&c| c != '\0')
+                .ok_or(err(ErrorKind::InvalidCharRef))?,
+            _ => return Err(err(ErrorKind::UnknownEntity)),
+        };
+
+        out.push(c);
+        Ok(true)
+    })
+}
+
+// ===== Error =====
+
+/// The error returned by [`unescape`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnescapeError {
+    offset: usize,
+    kind: ErrorKind,
+}
+
+/// The kind of an [`UnescapeError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// An `&` without a matching `;`.
+    Unterminated,
+    /// A named entity other than the 5 predefined ones.
+    UnknownEntity,
+    /// A numeric character reference that is malformed, or isn't a valid character.
+    InvalidCharRef,
+}
+
+impl UnescapeError {
+    /// Returns the byte offset of the `&` that starts the invalid reference.
+    #[inline]
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns what was wrong with the reference.
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl Display for UnescapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let msg = match self.kind {
+            ErrorKind::Unterminated => "unterminated reference",
+            ErrorKind::UnknownEntity => "unknown entity",
+            ErrorKind::InvalidCharRef => "invalid character reference",
+        };
+        write!(f, "{msg} at offset {}", self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnescapeError {}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unescape_str(s: &str) -> Result<MStr<'_>, UnescapeError> {
+        unescape(MStr::new_borrowed(s))
+    }
+
+    fn err(offset: usize, kind: ErrorKind) -> UnescapeError {
+        UnescapeError { offset, kind }
+    }
+
+    #[test]
+    fn escape_all() {
+        assert_eq!(
+            escape(MStr::new_borrowed("&<>\"'🐸")),
+            "&amp;&lt;&gt;&quot;&apos;🐸"
+        );
+
+        let owned = MStr::new_owned("owned");
+        let ptr = owned.as_ptr();
+        assert_eq!(escape(owned).as_ptr(), ptr);
+    }
+
+    #[test]
+    fn unescape_ok() {
+        assert!(unescape_str("nothing here").unwrap().is_borrowed());
+
+        assert_eq!(unescape_str("&amp;&lt;&gt;&quot;&apos;").unwrap(), "&<>\"'");
+        assert_eq!(unescape_str("x&#65;&#x42;y&#x1f438;").unwrap(), "xABy🐸");
+        assert_eq!(unescape_str("&amp;amp;").unwrap(), "&amp;");
+    }
+
+    #[test]
+    fn unescape_errors() {
+        assert_eq!(unescape_str("a & b"), Err(err(2, ErrorKind::Unterminated)));
+        assert_eq!(unescape_str("&amp"), Err(err(0, ErrorKind::Unterminated)));
+        assert_eq!(
+            unescape_str("🐸&nbsp;"),
+            Err(err(4, ErrorKind::UnknownEntity))
+        );
+        assert_eq!(unescape_str("&;"), Err(err(0, ErrorKind::UnknownEntity)));
+        assert_eq!(unescape_str("&#0;"), Err(err(0, ErrorKind::InvalidCharRef)));
+        assert_eq!(
+            unescape_str("&#xD800;"),
+            Err(err(0, ErrorKind::InvalidCharRef))
+        );
+        assert_eq!(unescape_str("&#x;"), Err(err(0, ErrorKind::InvalidCharRef)));
+        assert_eq!(
+            unescape_str("&#x110000;"),
+            Err(err(0, ErrorKind::InvalidCharRef))
+        );
+        // too big for a u32 is out of range like any other number
+        for s in ["&#99999999999;", "&#x100000000;"] {
+            assert_eq!(unescape_str(s), Err(err(0, ErrorKind::InvalidCharRef)));
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        for s in ["", "plain", "<&>\"'", "&amp;", "🐸 & 🐸"] {
+            assert_eq!(unescape(escape(MStr::new_borrowed(s))).unwrap(), s);
+        }
+    }
+
+    #[test]
+    fn error_fmt() {
+        assert_eq!(
+            alloc::format!("{}", err(7, ErrorKind::UnknownEntity)),
+            "unknown entity at offset 7"
+        );
+    }
+}
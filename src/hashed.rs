@@ -0,0 +1,447 @@
+use crate::MStr;
+use alloc::string::String;
+use core::cmp::Ordering;
+use core::fmt;
+use core::fmt::{Debug, Display, Formatter};
+use core::hash::{BuildHasherDefault, Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::Deref;
+use std::collections::hash_map::DefaultHasher;
+
+/// An `MStr` with a precomputed hash, for use as a map key.
+///
+/// The hash of the string is computed once (with a `H::default()` hasher) when the `HashedMStr` is created.
+/// Its [`Hash`] impl only writes that single `u64`,
+/// so a map using [`PassThroughBuildHasher<H>`] never rehashes the string data.
+///
+/// Requires the `std` feature.
+///
+/// # Hash flooding
+///
+/// The default `H` is [`DefaultHasher`], which (unlike the hashers from `RandomState`) always uses the same keys,
+/// so a string always gets the same hash, and [`PassThroughHasher`] uses it as-is.
+/// This means maps keyed by `HashedMStr` are *not* protected against keys crafted to collide (HashDoS),
+/// like `HashMap`s with the default `RandomState` are.
+///
+/// Don't use the default `H` for keys that come from untrusted input.
+/// Instead, use an `H` whose [`Default`] impl is keyed with a random seed
+/// (chosen once per process, so that every `HashedMStr` and the map's [`PassThroughHasher`] agree).
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::{HashedMStr, PassThroughBuildHasher};
+/// # use std::collections::HashMap;
+/// let mut map: HashMap<HashedMStr<'_>, _, PassThroughBuildHasher> = HashMap::default();
+/// map.insert(HashedMStr::new("foo"), 1);
+/// map.insert(HashedMStr::new(String::from("bar")), 2);
+///
+/// assert_eq!(map.get(&HashedMStr::new("bar")), Some(&2));
+/// ```
+///
+/// With the `hashbrown` feature, maps using [`PassThroughBuildHasher<H>`]
+/// can also be queried with a plain `str` (see [`Equivalent`](hashbrown::Equivalent)):
+///
+/// ```rust
+/// # #[cfg(feature = "hashbrown")] {
+/// # use mstr::{HashedMStr, PassThroughBuildHasher};
+/// let mut map: hashbrown::HashMap<HashedMStr<'_>, _, PassThroughBuildHasher> =
+///     hashbrown::HashMap::default();
+/// map.insert(HashedMStr::new("foo"), 1);
+///
+/// assert_eq!(map.get("foo"), Some(&1));
+/// # }
+/// ```
+pub struct HashedMStr<'a, H = DefaultHasher> {
+    hash: u64,
+    s: MStr<'a>,
+    _hasher: PhantomData<fn() -> H>,
+}
+
+impl<'a, H: Hasher + Default> HashedMStr<'a, H> {
+    /// Creates a new `HashedMStr`, hashing the string with `H::default()`.
+    ///
+    /// The input type is anything that can be converted into an `MStr<'a>`,
+    /// and the borrowed/owned state of the string is preserved.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::HashedMStr;
+    /// let hashed: HashedMStr<'_> = HashedMStr::new("foo");
+    ///
+    /// assert_eq!(hashed, "foo");
+    /// assert!(hashed.as_mstr().is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn new(s: impl Into<MStr<'a>>) -> HashedMStr<'a, H> {
+        let s = s.into();
+        HashedMStr {
+            hash: hash_str::<H>(&s),
+            s,
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Hashes every string of an iterator, producing an iterator of `HashedMStr`s.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::{HashedMStr, MStr};
+    /// let strings = vec![MStr::new_borrowed("a"), MStr::new_owned("b")];
+    /// let hashed: Vec<HashedMStr<'_>> = HashedMStr::hash_all(strings).collect();
+    ///
+    /// assert_eq!(hashed, ["a", "b"]);
+    /// assert!(hashed[1].as_mstr().is_owned());
+    /// ```
+    pub fn hash_all<I>(strings: I) -> impl Iterator<Item = HashedMStr<'a, H>>
+    where
+        I: IntoIterator,
+        I::Item: Into<MStr<'a>>,
+    {
+        strings.into_iter().map(HashedMStr::new)
+    }
+}
+
+impl<'a, H> HashedMStr<'a, H> {
+    /// Gets the precomputed hash of the string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::HashedMStr;
+    /// let a: HashedMStr<'_> = HashedMStr::new("foo");
+    /// let b: HashedMStr<'_> = HashedMStr::new(String::from("foo"));
+    ///
+    /// assert_eq!(a.hash_value(), b.hash_value());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn hash_value(&self) -> u64 {
+        self.hash
+    }
+
+    /// Gets a reference to the underlying `MStr`.
+    #[inline]
+    #[must_use]
+    pub const fn as_mstr(&self) -> &MStr<'a> {
+        &self.s
+    }
+
+    /// Converts this `HashedMStr` to a string slice.
+    #[inline]
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        self.s.as_str()
+    }
+
+    /// Converts this `HashedMStr` back into the underlying `MStr`, discarding the hash.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> MStr<'a> {
+        self.s
+    }
+}
+
+fn hash_str<H: Hasher + Default>(s: &str) -> u64 {
+    // hash through the pass-through hasher (rather than H directly),
+    // so that the result always matches what it produces when hashing a str
+    let mut hasher = PassThroughHasher::<H>::default();
+    s.hash(&mut hasher);
+    hasher.finish()
+}
+
+// ===== Pass-Through Hasher =====
+
+/// A hasher that passes the precomputed hash of a [`HashedMStr`] straight through.
+///
+/// When hashing a `HashedMStr`, the precomputed hash is returned as-is.
+/// When hashing anything else (such as a `str`), the data is forwarded to an inner `H`,
+/// which produces the same hash that `HashedMStr` would have precomputed for it.
+///
+/// Use it with [`PassThroughBuildHasher<H>`].
+#[derive(Default)]
+pub struct PassThroughHasher<H = DefaultHasher> {
+    inner: H,
+    hash: Option<u64>,
+}
+
+/// A [`BuildHasher`](core::hash::BuildHasher) for [`PassThroughHasher<H>`].
+///
+/// Use this as the hasher of maps keyed by [`HashedMStr<H>`].
+///
+/// This is only as resistant to hash flooding as `H::default()` is,
+/// which for the default [`DefaultHasher`] means not at all (see [`HashedMStr`'s docs](HashedMStr#hash-flooding)).
+pub type PassThroughBuildHasher<H = DefaultHasher> = BuildHasherDefault<PassThroughHasher<H>>;
+
+impl<H: Hasher> Hasher for PassThroughHasher<H> {
+    #[inline]
+    fn finish(&self) -> u64 {
+        match self.hash {
+            Some(hash) => hash,
+            None => self.inner.finish(),
+        }
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write(bytes);
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.inner.write_u8(i);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        // only HashedMStr calls write_u64 (str never does)
+        self.hash = Some(i);
+    }
+}
+
+impl<H> Debug for PassThroughHasher<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PassThroughHasher")
+            .field("hash", &self.hash)
+            .finish_non_exhaustive()
+    }
+}
+
+// ===== Trait Impls =====
+
+impl<H> Clone for HashedMStr<'_, H> {
+    /// Clones this `HashedMStr`, without rehashing.
+    ///
+    /// See [`MStr::clone`].
+    fn clone(&self) -> Self {
+        HashedMStr {
+            hash: self.hash,
+            s: self.s.clone(),
+            _hasher: PhantomData,
+        }
+    }
+}
+
+// -- Format --
+
+impl<H> Debug for HashedMStr<'_, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<H> Display for HashedMStr<'_, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+// -- Convert --
+
+impl<'a, H: Hasher + Default> From<MStr<'a>> for HashedMStr<'a, H> {
+    fn from(value: MStr<'a>) -> Self {
+        HashedMStr::new(value)
+    }
+}
+
+impl<'a, H: Hasher + Default> From<&'a str> for HashedMStr<'a, H> {
+    fn from(value: &'a str) -> Self {
+        HashedMStr::new(value)
+    }
+}
+
+impl<H: Hasher + Default> From<String> for HashedMStr<'_, H> {
+    fn from(value: String) -> Self {
+        HashedMStr::new(value)
+    }
+}
+
+impl<'a, H> From<HashedMStr<'a, H>> for MStr<'a> {
+    fn from(value: HashedMStr<'a, H>) -> Self {
+        value.into_inner()
+    }
+}
+
+impl<H> Deref for HashedMStr<'_, H> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<H> AsRef<str> for HashedMStr<'_, H> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+// no Borrow<str>, because the Hash impls are different
+// (use hashbrown's Equivalent with PassThroughBuildHasher instead)
+
+// -- Hash --
+
+impl<H> Hash for HashedMStr<'_, H> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.hash);
+    }
+}
+
+// -- [Partial]Eq --
+
+impl<H> Eq for HashedMStr<'_, H> {}
+
+impl<H> PartialEq for HashedMStr<'_, H> {
+    fn eq(&self, other: &Self) -> bool {
+        // different hashes can never be equal, and comparing them is cheap
+        self.hash == other.hash && self.s == other.s
+    }
+}
+
+impl<H> PartialEq<str> for HashedMStr<'_, H> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<H> PartialEq<&str> for HashedMStr<'_, H> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<H> PartialEq<MStr<'_>> for HashedMStr<'_, H> {
+    fn eq(&self, other: &MStr<'_>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+// -- [Partial]Ord --
+
+impl<H> Ord for HashedMStr<'_, H> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.s.cmp(&other.s)
+    }
+}
+
+impl<H> PartialOrd for HashedMStr<'_, H> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// -- Equivalent --
+
+#[cfg(any(feature = "hashbrown", feature = "indexmap"))]
+mod equivalent_impls {
+    use super::*;
+    use equivalent::Equivalent;
+
+    // these are only correct if the map uses PassThroughBuildHasher<H>,
+    // because otherwise the str won't hash to the precomputed hash
+
+    impl<H> Equivalent<HashedMStr<'_, H>> for str {
+        fn equivalent(&self, key: &HashedMStr<'_, H>) -> bool {
+            self == key.as_str()
+        }
+    }
+
+    impl<H> Equivalent<HashedMStr<'_, H>> for MStr<'_> {
+        fn equivalent(&self, key: &HashedMStr<'_, H>) -> bool {
+            self.as_str() == key.as_str()
+        }
+    }
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use std::collections::HashMap;
+
+    #[test]
+    fn hash_matches_str() {
+        let h: HashedMStr<'_> = HashedMStr::new("foo");
+
+        let mut hasher = PassThroughHasher::<DefaultHasher>::default();
+        "foo".hash(&mut hasher);
+
+        assert_eq!(h.hash_value(), hasher.finish());
+    }
+
+    #[test]
+    fn hash_passes_through() {
+        let h: HashedMStr<'_> = HashedMStr::new("bar");
+
+        let mut hasher = PassThroughHasher::<DefaultHasher>::default();
+        h.hash(&mut hasher);
+
+        assert_eq!(hasher.finish(), h.hash_value());
+    }
+
+    #[test]
+    fn borrowed_owned_same_hash() {
+        let a: HashedMStr<'_> = HashedMStr::new(MStr::new_borrowed("quack"));
+        let b: HashedMStr<'_> = HashedMStr::new(MStr::new_owned("quack"));
+        let c: HashedMStr<'_> = HashedMStr::new("honk");
+
+        assert_eq!(a.hash_value(), b.hash_value());
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        assert!(a.as_mstr().is_borrowed());
+        assert!(b.as_mstr().is_owned());
+        assert!(b.clone().as_mstr().is_owned());
+        assert_eq!(b.clone().hash_value(), b.hash_value());
+    }
+
+    #[test]
+    fn std_map() {
+        let mut map: HashMap<HashedMStr<'_>, _, PassThroughBuildHasher> = HashMap::default();
+        for (i, s) in ["a", "b", "c"].into_iter().enumerate() {
+            map.insert(HashedMStr::new(s), i);
+        }
+
+        assert_eq!(map.get(&HashedMStr::new(String::from("b"))), Some(&1));
+        assert_eq!(map.get(&HashedMStr::new("d")), None);
+    }
+
+    #[test]
+    fn hash_all() {
+        let hashed = HashedMStr::<DefaultHasher>::hash_all(["x", "y"]).collect::<Vec<_>>();
+
+        assert_eq!(hashed, ["x", "y"]);
+        assert_eq!(hashed[0].hash_value(), hash_str::<DefaultHasher>("x"));
+        assert_eq!(hashed[0].clone().into_inner(), "x");
+    }
+
+    #[test]
+    #[cfg(feature = "hashbrown")]
+    fn hashbrown_equivalent() {
+        let mut map: hashbrown::HashMap<HashedMStr<'_>, _, PassThroughBuildHasher> =
+            hashbrown::HashMap::default();
+        map.insert(HashedMStr::new("foo"), 1);
+        map.insert(HashedMStr::new(String::from("bar")), 2);
+
+        assert_eq!(map.get("foo"), Some(&1));
+        assert_eq!(map.get(&MStr::new_owned("bar")), Some(&2));
+        assert_eq!(map.get("baz"), None);
+
+        // shorter lifetimes work too
+        let s = String::from("foo");
+        assert_eq!(map.get(s.as_str()), Some(&1));
+        assert_eq!(map.get(&MStr::new_borrowed(&s)), Some(&1));
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<HashedMStr<'_>>();
+        assert_send_sync::<HashedMStr<'static>>();
+    }
+}
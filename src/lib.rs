@@ -4,6 +4,73 @@
 #![deny(elided_lifetimes_in_paths)]
 
 extern crate alloc;
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+mod ascii;
+pub mod case;
+pub mod case_insensitive;
+mod decode;
+#[cfg(feature = "encoding_rs")]
+pub mod encoding;
+pub mod escape;
+#[cfg(feature = "std")]
+mod hashed;
+#[cfg(any(feature = "hashbrown", feature = "indexmap"))]
+pub mod map;
+mod newtype;
+mod prefix;
+#[cfg(feature = "regex")]
+pub mod regex;
+#[cfg(feature = "segmentation")]
+pub mod segment;
+pub mod shell;
+pub mod table;
+pub mod template;
+mod transform;
+#[cfg(feature = "unicode")]
+mod unicode;
+mod utf8;
+pub mod vec;
+mod whitespace;
+#[cfg(feature = "width")]
+mod width;
+
+pub use ascii::{AsciiMStr, NotAsciiError};
+pub use case_insensitive::CaseInsensitive;
+pub use decode::{EncodeUtf16, FromUtf16Error};
+#[cfg(feature = "std")]
+pub use hashed::{HashedMStr, PassThroughBuildHasher, PassThroughHasher};
+pub use prefix::PrefixMStr;
+pub use transform::Transform;
+pub use utf8::{FromUtf8Error, Utf8Input};
+pub use vec::MStrVec;
+
+/// An `MStr` that is compared, ordered and hashed ignoring case, using Unicode case folding.
+///
+/// See [`case_insensitive`] for more info.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::{MStr, UniCaseMStr};
+/// let s = UniCaseMStr::new(MStr::new_borrowed("Straße"));
+/// assert_eq!(s, UniCaseMStr::new(MStr::new_borrowed("STRASSE")));
+/// ```
+pub type UniCaseMStr<'a> = CaseInsensitive<MStr<'a>, case_insensitive::Unicode>;
+
+// used by macro expansions, not public API
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::borrow::Cow;
+    pub use alloc::boxed::Box;
+    pub use alloc::string::String;
+
+    #[cfg(feature = "serde")]
+    pub use serde;
+}
 
 use alloc::borrow::Cow;
 use alloc::boxed::Box;
@@ -459,6 +526,20 @@ impl<'a> MStr<'a> {
     pub const fn as_str_ptr(&self) -> *const str {
         ptr::slice_from_raw_parts::<u8>(self.as_ptr(), self.len()) as *const str
     }
+
+    // `sub` as an `MStr<'a>`, borrowed from `src` if there is one, and copied otherwise.
+    // `sub` must be a slice of `src` (when it is `Some`).
+    #[cfg(any(feature = "segmentation", feature = "regex"))]
+    #[inline]
+    pub(crate) fn new_subslice(src: Option<&'a str>, sub: &str) -> MStr<'a> {
+        match src {
+            Some(src) => {
+                let start = sub.as_ptr() as usize - src.as_ptr() as usize;
+                MStr::new_borrowed(&src[start..start + sub.len()])
+            }
+            None => MStr::new_owned(sub),
+        }
+    }
 }
 
 // ===== Trait Impls =====
@@ -0,0 +1,315 @@
+//! `MStr`-keyed map aliases and helpers for [hashbrown](https://docs.rs/hashbrown) and [indexmap](https://docs.rs/indexmap).
+//!
+//! Requires the `hashbrown` and/or `indexmap` feature.
+//!
+//! With either feature enabled, [`Equivalent`] is also implemented between `MStr` and `String` or `&str`,
+//! so maps keyed by one can be queried with the other without allocating.
+//! (`str` already works through `Borrow<str>`.)
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "hashbrown")] {
+//! # use mstr::MStr;
+//! use mstr::map::{MStrHashMap, MStrMapExt};
+//!
+//! let mut counts = MStrHashMap::<'_, usize>::default();
+//!
+//! for word in "the cat and the hat".split(' ') {
+//!     // the words borrow from the input, so the keys never allocate
+//!     *counts.entry_or_insert_borrowed(word, || 0) += 1;
+//! }
+//!
+//! assert_eq!(counts.get("the"), Some(&2));
+//! assert_eq!(counts.get(&String::from("cat")), Some(&1));
+//! assert!(counts.keys().all(MStr::is_borrowed));
+//!
+//! // `MStr` is covariant, so a map with `'static` keys can be queried with a shorter-lived `MStr`
+//! let mut statics = MStrHashMap::<'static, usize>::default();
+//! statics.insert(MStr::new_borrowed("cat"), 1);
+//!
+//! let owner = String::from("cat");
+//! assert_eq!(statics.get(&MStr::new_borrowed(&owner)), Some(&1));
+//! # }
+//! ```
+
+use crate::MStr;
+use alloc::string::String;
+use core::hash::BuildHasher;
+use equivalent::Equivalent;
+
+// ===== Equivalent =====
+
+// (the blanket impl already covers str, and MStr itself for the same lifetime)
+
+impl Equivalent<MStr<'_>> for String {
+    #[inline]
+    fn equivalent(&self, key: &MStr<'_>) -> bool {
+        self.as_str() == key.as_str()
+    }
+}
+
+impl Equivalent<String> for MStr<'_> {
+    #[inline]
+    fn equivalent(&self, key: &String) -> bool {
+        self.as_str() == key.as_str()
+    }
+}
+
+impl Equivalent<MStr<'_>> for &str {
+    #[inline]
+    fn equivalent(&self, key: &MStr<'_>) -> bool {
+        *self == key.as_str()
+    }
+}
+
+impl Equivalent<&str> for MStr<'_> {
+    #[inline]
+    fn equivalent(&self, key: &&str) -> bool {
+        self.as_str() == *key
+    }
+}
+
+// ===== Aliases =====
+
+/// A [`hashbrown::HashMap`] keyed by `MStr`.
+///
+/// Requires the `hashbrown` feature.
+#[cfg(feature = "hashbrown")]
+pub type MStrHashMap<'a, V, S = hashbrown::DefaultHashBuilder> = hashbrown::HashMap<MStr<'a>, V, S>;
+
+/// A [`hashbrown::HashSet`] of `MStr`s.
+///
+/// Requires the `hashbrown` feature.
+#[cfg(feature = "hashbrown")]
+pub type MStrHashSet<'a, S = hashbrown::DefaultHashBuilder> = hashbrown::HashSet<MStr<'a>, S>;
+
+/// An [`indexmap::IndexMap`] keyed by `MStr`.
+///
+/// Requires the `indexmap` feature.
+#[cfg(feature = "indexmap")]
+pub type MStrIndexMap<'a, V, S = std::collections::hash_map::RandomState> =
+    indexmap::IndexMap<MStr<'a>, V, S>;
+
+/// An [`indexmap::IndexSet`] of `MStr`s.
+///
+/// Requires the `indexmap` feature.
+#[cfg(feature = "indexmap")]
+pub type MStrIndexSet<'a, S = std::collections::hash_map::RandomState> =
+    indexmap::IndexSet<MStr<'a>, S>;
+
+// ===== Entry Helpers =====
+
+/// Entry helpers for maps keyed by `MStr<'a>`, which only create a key when it is missing.
+///
+/// Both methods look the key up as a `str` first, so they never allocate for keys that are already present.
+///
+/// Implemented for [`hashbrown::HashMap`] (with the `hashbrown` feature)
+/// and [`indexmap::IndexMap`] (with the `indexmap` feature).
+pub trait MStrMapExt<'a, V> {
+    /// Gets a mutable reference to the value for `key`,
+    /// inserting `default()` with a borrowed key if it is missing.
+    ///
+    /// This never allocates a key.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "hashbrown")] {
+    /// # use mstr::map::{MStrHashMap, MStrMapExt};
+    /// let mut map = MStrHashMap::<'_, Vec<i32>>::default();
+    /// map.entry_or_insert_borrowed("foo", Vec::new).push(1);
+    /// map.entry_or_insert_borrowed("foo", Vec::new).push(2);
+    ///
+    /// assert_eq!(map["foo"], [1, 2]);
+    /// assert!(map.keys().next().unwrap().is_borrowed());
+    /// # }
+    /// ```
+    fn entry_or_insert_borrowed<F>(&mut self, key: &'a str, default: F) -> &mut V
+    where
+        F: FnOnce() -> V;
+
+    /// Gets a mutable reference to the value for `key`,
+    /// inserting `default()` with an owned copy of the key if it is missing.
+    ///
+    /// Use this when `key` doesn't live for `'a`.
+    /// The key is only copied if it is inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "hashbrown")] {
+    /// # use mstr::map::{MStrHashMap, MStrMapExt};
+    /// let mut map = MStrHashMap::<'static, i32>::default();
+    ///
+    /// let temp = String::from("foo");
+    /// *map.entry_or_insert_owned(&temp, || 0) += 1;
+    /// drop(temp);
+    ///
+    /// assert_eq!(map["foo"], 1);
+    /// assert!(map.keys().next().unwrap().is_owned());
+    /// # }
+    /// ```
+    fn entry_or_insert_owned<F>(&mut self, key: &str, default: F) -> &mut V
+    where
+        F: FnOnce() -> V;
+}
+
+#[cfg(feature = "hashbrown")]
+impl<'a, V, S: BuildHasher> MStrMapExt<'a, V> for hashbrown::HashMap<MStr<'a>, V, S> {
+    fn entry_or_insert_borrowed<F>(&mut self, key: &'a str, default: F) -> &mut V
+    where
+        F: FnOnce() -> V,
+    {
+        self.entry_ref(key).or_insert_with(default)
+    }
+
+    fn entry_or_insert_owned<F>(&mut self, key: &str, default: F) -> &mut V
+    where
+        F: FnOnce() -> V,
+    {
+        use hashbrown::hash_map::EntryRef;
+
+        match self.entry_ref(key) {
+            EntryRef::Occupied(e) => e.into_mut(),
+            EntryRef::Vacant(e) => e.insert_with_key(MStr::new_owned(key), default()),
+        }
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<'a, V, S: BuildHasher> MStrMapExt<'a, V> for indexmap::IndexMap<MStr<'a>, V, S> {
+    fn entry_or_insert_borrowed<F>(&mut self, key: &'a str, default: F) -> &mut V
+    where
+        F: FnOnce() -> V,
+    {
+        entry_or_insert_index(self, key, || MStr::new_borrowed(key), default)
+    }
+
+    fn entry_or_insert_owned<F>(&mut self, key: &str, default: F) -> &mut V
+    where
+        F: FnOnce() -> V,
+    {
+        entry_or_insert_index(self, key, || MStr::new_owned(key), default)
+    }
+}
+
+#[cfg(feature = "indexmap")]
+fn entry_or_insert_index<'m, 'a, V, S: BuildHasher>(
+    map: &'m mut indexmap::IndexMap<MStr<'a>, V, S>,
+    key: &str,
+    make_key: impl FnOnce() -> MStr<'a>,
+    default: impl FnOnce() -> V,
+) -> &'m mut V {
+    let index = match map.get_index_of(key) {
+        Some(index) => index,
+        None => map.insert_full(make_key(), default()).0,
+    };
+    &mut map[index]
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn equivalent() {
+        let m = MStr::new_borrowed("foo");
+
+        assert!(String::from("foo").equivalent(&m));
+        assert!(m.equivalent(&String::from("foo")));
+        assert!("foo".equivalent(&m));
+        assert!(m.equivalent(&"foo"));
+        assert!(!"bar".equivalent(&m));
+    }
+
+    #[test]
+    #[cfg(feature = "hashbrown")]
+    fn hashbrown_lookup() {
+        let mut map = MStrHashMap::<'_, i32>::default();
+        map.insert(MStr::new_borrowed("foo"), 1);
+        map.insert(MStr::new_owned("bar"), 2);
+
+        assert_eq!(map.get("foo"), Some(&1));
+        assert_eq!(map.get(&"bar".to_string()), Some(&2));
+        assert_eq!(map.get(&"bar"), Some(&2));
+        assert_eq!(map.get(&MStr::new_owned("foo")), Some(&1));
+
+        // a String-keyed map queried with an MStr
+        let mut map = hashbrown::HashMap::<String, i32>::default();
+        map.insert("foo".to_string(), 1);
+        assert_eq!(map.get(&MStr::new_borrowed("foo")), Some(&1));
+    }
+
+    #[test]
+    #[cfg(feature = "hashbrown")]
+    fn shorter_lifetime_lookup() {
+        let mut map = MStrHashMap::<'static, i32>::default();
+        map.insert(MStr::new_borrowed("foo"), 1);
+
+        let owner = "foo".to_string();
+        let key = MStr::new_borrowed(&owner);
+        assert_eq!(map.get(&key), Some(&1));
+
+        let mut std_map = std::collections::HashMap::<MStr<'static>, i32>::new();
+        std_map.insert(MStr::new_borrowed("foo"), 1);
+        assert_eq!(std_map.get(&key), Some(&1));
+    }
+
+    #[test]
+    #[cfg(feature = "hashbrown")]
+    fn hashbrown_entry() {
+        let mut map = MStrHashMap::<'_, i32>::default();
+
+        *map.entry_or_insert_borrowed("a", || 0) += 1;
+        *map.entry_or_insert_borrowed("a", || 0) += 1;
+        *map.entry_or_insert_owned("b", || 0) += 1;
+        let temp = String::from("a");
+        *map.entry_or_insert_owned(&temp, || 0) += 1;
+
+        assert_eq!(map["a"], 3);
+        assert_eq!(map["b"], 1);
+        assert!(map.get_key_value("a").unwrap().0.is_borrowed());
+        assert!(map.get_key_value("b").unwrap().0.is_owned());
+    }
+
+    #[test]
+    #[cfg(feature = "indexmap")]
+    fn indexmap_lookup() {
+        let mut map = MStrIndexMap::<'_, i32>::default();
+        map.insert(MStr::new_borrowed("foo"), 1);
+        map.insert(MStr::new_owned("bar"), 2);
+
+        assert_eq!(map.get("foo"), Some(&1));
+        assert_eq!(map.get(&"bar".to_string()), Some(&2));
+        assert_eq!(map.get_index_of(&"bar"), Some(1));
+    }
+
+    #[test]
+    #[cfg(feature = "indexmap")]
+    fn indexmap_entry() {
+        let mut map = MStrIndexMap::<'_, i32>::default();
+
+        *map.entry_or_insert_owned("b", || 0) += 1;
+        *map.entry_or_insert_borrowed("a", || 0) += 1;
+        *map.entry_or_insert_borrowed("b", || 0) += 1;
+
+        assert_eq!(map.get_index(0), Some((&MStr::from("b"), &2)));
+        assert_eq!(map.get_index(1), Some((&MStr::from("a"), &1)));
+        assert!(map.get_index(0).unwrap().0.is_owned());
+        assert!(map.get_index(1).unwrap().0.is_borrowed());
+    }
+
+    #[test]
+    #[cfg(feature = "hashbrown")]
+    fn set() {
+        let mut set: MStrHashSet<'_> = MStrHashSet::default();
+        set.insert(MStr::new_borrowed("a"));
+
+        assert!(set.contains("a"));
+        assert!(set.contains(&"a".to_string()));
+    }
+}
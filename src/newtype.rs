@@ -0,0 +1,437 @@
+// the `mstr_newtype!` macro
+// (macro_export puts it at the crate root, this module only exists to keep lib.rs tidy)
+
+/// Declares a newtype around [`MStr<'a>`](crate::MStr) that can only hold strings which pass a validation function.
+///
+/// ```text
+/// mstr_newtype! {
+///     /// docs and other attributes
+///     pub struct Name<'a> {
+///         validate: <fn(&str) -> Result<(), Error>>,
+///         error: <Error>,
+///     }
+/// }
+/// ```
+///
+/// The validation function can be any expression callable as `fn(&str) -> Result<(), Error>`
+/// (a path to a function, or a closure).
+/// The error type must implement [`Display`](core::fmt::Display).
+///
+/// The generated type gets:
+/// - `new(impl Into<MStr<'a>>) -> Result<Self, Error>`, `as_str`, `as_mstr` and `into_inner`
+/// - `TryFrom` from every type `MStr` has a `From` impl for (`&str`, `&mut str`, `String`, `Box<str>`, `Cow<str>`),
+///   and from `MStr` itself
+/// - `FromStr` (which always creates an owned string)
+/// - `From<Name<'a>>` for `MStr<'a>` and `String`
+/// - `Clone`, `Debug`, `Display`, `Hash`, `Eq`, `Ord`, `Deref<Target = str>`, `AsRef<str>` and `Borrow<str>`
+/// - `PartialEq` with `str`, `&str`, `String` and `MStr`, in both directions
+/// - with the `serde` feature, `Serialize` and a `Deserialize` that validates
+///   (and, like `MStr`, always deserializes to an owned string)
+///
+/// # Examples
+///
+/// ```rust
+/// use mstr::{mstr_newtype, MStr};
+/// use std::fmt;
+///
+/// #[derive(Debug, PartialEq)]
+/// pub struct EmptyError;
+///
+/// impl fmt::Display for EmptyError {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         f.write_str("string is empty")
+///     }
+/// }
+///
+/// fn non_empty(s: &str) -> Result<(), EmptyError> {
+///     if s.is_empty() {
+///         Err(EmptyError)
+///     } else {
+///         Ok(())
+///     }
+/// }
+///
+/// mstr_newtype! {
+///     /// A string that is never empty.
+///     pub struct NonEmpty<'a> {
+///         validate: non_empty,
+///         error: EmptyError,
+///     }
+/// }
+///
+/// let name = NonEmpty::new("frog").unwrap();
+/// assert_eq!(name, "frog");
+/// assert_eq!(name.len(), 4); // derefs to str
+/// assert!(name.as_mstr().is_borrowed());
+///
+/// assert_eq!(NonEmpty::try_from(String::new()), Err(EmptyError));
+/// assert_eq!("".parse::<NonEmpty<'_>>(), Err(EmptyError));
+///
+/// let inner: MStr<'_> = name.into_inner();
+/// assert_eq!(inner, "frog");
+/// ```
+#[macro_export]
+macro_rules! mstr_newtype {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident<$lt:lifetime> {
+            validate: $validate:expr,
+            error: $err:ty $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        $vis struct $name<$lt>($crate::MStr<$lt>);
+
+        // the serde impls need this, so require it regardless of which features are enabled
+        const _: fn() = || {
+            fn assert_display<T: ::core::fmt::Display + ?::core::marker::Sized>() {}
+            assert_display::<$err>();
+        };
+
+        // not every newtype needs every accessor
+        #[allow(dead_code)]
+        impl<$lt> $name<$lt> {
+            /// Validates `s`, and wraps it if it is valid.
+            #[inline]
+            pub fn new(s: impl ::core::convert::Into<$crate::MStr<$lt>>) -> ::core::result::Result<Self, $err> {
+                let s = s.into();
+                match ($validate)(s.as_str()) {
+                    ::core::result::Result::Ok(()) => ::core::result::Result::Ok(Self(s)),
+                    ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
+                }
+            }
+
+            /// Returns the inner string as a `&str`.
+            #[inline]
+            pub fn as_str(&self) -> &str {
+                self.0.as_str()
+            }
+
+            /// Returns a reference to the inner `MStr`.
+            #[inline]
+            pub fn as_mstr(&self) -> &$crate::MStr<$lt> {
+                &self.0
+            }
+
+            /// Unwraps the inner `MStr`.
+            #[inline]
+            pub fn into_inner(self) -> $crate::MStr<$lt> {
+                self.0
+            }
+        }
+
+        // -- Conversions --
+
+        $crate::__mstr_newtype_try_from!(
+            $name, $lt, $err,
+            $crate::MStr<$lt>,
+            &$lt str,
+            &$lt mut str,
+            $crate::__private::Cow<$lt, str>,
+            $crate::__private::String,
+            $crate::__private::Box<str>,
+        );
+
+        impl ::core::str::FromStr for $name<'_> {
+            type Err = $err;
+
+            #[inline]
+            fn from_str(s: &str) -> ::core::result::Result<Self, $err> {
+                Self::new($crate::MStr::new_owned(s))
+            }
+        }
+
+        impl<$lt> ::core::convert::From<$name<$lt>> for $crate::MStr<$lt> {
+            #[inline]
+            fn from(s: $name<$lt>) -> Self {
+                s.0
+            }
+        }
+
+        impl ::core::convert::From<$name<'_>> for $crate::__private::String {
+            #[inline]
+            fn from(s: $name<'_>) -> Self {
+                s.0.into_string()
+            }
+        }
+
+        // -- Access --
+
+        impl ::core::ops::Deref for $name<'_> {
+            type Target = str;
+
+            #[inline]
+            fn deref(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl ::core::convert::AsRef<str> for $name<'_> {
+            #[inline]
+            fn as_ref(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl ::core::borrow::Borrow<str> for $name<'_> {
+            #[inline]
+            fn borrow(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl ::core::fmt::Display for $name<'_> {
+            #[inline]
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(self.0.as_str(), f)
+            }
+        }
+
+        // -- Comparisons --
+
+        $crate::__mstr_newtype_eq!($name, str, $crate::__private::String, $crate::MStr<'_>);
+
+        impl ::core::cmp::PartialEq<&str> for $name<'_> {
+            #[inline]
+            fn eq(&self, other: &&str) -> bool {
+                self.0.as_str() == *other
+            }
+        }
+
+        impl ::core::cmp::PartialEq<$name<'_>> for &str {
+            #[inline]
+            fn eq(&self, other: &$name<'_>) -> bool {
+                *self == other.0.as_str()
+            }
+        }
+
+        // -- serde --
+
+        $crate::__mstr_newtype_serde!($name, $lt);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __mstr_newtype_try_from {
+    ($name:ident, $lt:lifetime, $err:ty, $($from:ty,)*) => {
+        $(
+            impl<$lt> ::core::convert::TryFrom<$from> for $name<$lt> {
+                type Error = $err;
+
+                #[inline]
+                fn try_from(s: $from) -> ::core::result::Result<Self, $err> {
+                    Self::new(s)
+                }
+            }
+        )*
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __mstr_newtype_eq {
+    ($name:ident, $($other:ty),*) => {
+        $(
+            impl ::core::cmp::PartialEq<$other> for $name<'_> {
+                #[inline]
+                fn eq(&self, other: &$other) -> bool {
+                    self.0.as_str() == ::core::convert::AsRef::<str>::as_ref(other)
+                }
+            }
+
+            impl ::core::cmp::PartialEq<$name<'_>> for $other {
+                #[inline]
+                fn eq(&self, other: &$name<'_>) -> bool {
+                    ::core::convert::AsRef::<str>::as_ref(self) == other.0.as_str()
+                }
+            }
+        )*
+    };
+}
+
+// the serde impls have to be chosen by *this* crate's features,
+// since a `cfg` in the expansion would check the caller's features instead
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __mstr_newtype_serde {
+    ($name:ident, $lt:lifetime) => {
+        impl $crate::__private::serde::Serialize for $name<'_> {
+            fn serialize<S>(&self, s: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                s.serialize_str(self.0.as_str())
+            }
+        }
+
+        impl<'de, $lt> $crate::__private::serde::Deserialize<'de> for $name<$lt> {
+            fn deserialize<D>(d: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                let s =
+                    <$crate::MStr<$lt> as $crate::__private::serde::Deserialize<'de>>::deserialize(
+                        d,
+                    )?;
+                Self::new(s).map_err(<D::Error as $crate::__private::serde::de::Error>::custom)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __mstr_newtype_serde {
+    ($name:ident, $lt:lifetime) => {};
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use crate::MStr;
+    use alloc::borrow::Cow;
+    use alloc::boxed::Box;
+    use alloc::string::{String, ToString};
+    use core::fmt;
+
+    #[derive(Debug, PartialEq)]
+    struct InvalidIdent(usize);
+
+    impl fmt::Display for InvalidIdent {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "invalid identifier character at {}", self.0)
+        }
+    }
+
+    fn ident(s: &str) -> Result<(), InvalidIdent> {
+        match s.find(|c: char| !c.is_ascii_alphanumeric() && c != '_') {
+            Some(i) => Err(InvalidIdent(i)),
+            None if s.is_empty() => Err(InvalidIdent(0)),
+            None => Ok(()),
+        }
+    }
+
+    mstr_newtype! {
+        /// An ASCII identifier.
+        struct Ident<'a> {
+            validate: ident,
+            error: InvalidIdent,
+        }
+    }
+
+    mstr_newtype! {
+        struct Short<'a> {
+            validate: |s: &str| if s.len() <= 3 { Ok(()) } else { Err(InvalidIdent(3)) },
+            error: InvalidIdent
+        }
+    }
+
+    #[test]
+    fn new() {
+        let id = Ident::new("foo_bar").unwrap();
+        assert_eq!(id.as_str(), "foo_bar");
+        assert!(id.as_mstr().is_borrowed());
+
+        assert_eq!(Ident::new("foo bar"), Err(InvalidIdent(3)));
+        assert_eq!(Ident::new(""), Err(InvalidIdent(0)));
+
+        assert!(Short::new("abc").is_ok());
+        assert_eq!(Short::new("abcd"), Err(InvalidIdent(3)));
+    }
+
+    #[test]
+    fn try_from() {
+        let mut buf = String::from("mut");
+
+        assert!(Ident::try_from("a").unwrap().as_mstr().is_borrowed());
+        assert!(Ident::try_from(buf.as_mut_str()).is_ok());
+        assert!(Ident::try_from(String::from("a"))
+            .unwrap()
+            .as_mstr()
+            .is_owned());
+        assert!(Ident::try_from(Box::<str>::from("a")).is_ok());
+        assert!(Ident::try_from(Cow::Borrowed("a")).is_ok());
+        assert!(Ident::try_from(MStr::new_owned("a")).is_ok());
+
+        assert_eq!(Ident::try_from("-"), Err(InvalidIdent(0)));
+    }
+
+    #[test]
+    fn from_str() {
+        let id: Ident<'static> = "abc".to_string().parse().unwrap();
+        assert!(id.as_mstr().is_owned());
+
+        assert_eq!("a-c".parse::<Ident<'_>>(), Err(InvalidIdent(1)));
+    }
+
+    #[test]
+    fn into_inner() {
+        let id = Ident::new("abc").unwrap();
+
+        assert_eq!(MStr::from(id.clone()), "abc");
+        assert_eq!(String::from(id.clone()), "abc");
+        assert_eq!(id.into_inner(), "abc");
+    }
+
+    #[test]
+    fn traits() {
+        let id = Ident::new("abc").unwrap();
+
+        assert_eq!(id.len(), 3);
+        assert_eq!(id.to_string(), "abc");
+        assert_eq!(alloc::format!("{id:?}"), "Ident(\"abc\")");
+        assert!(id < Ident::new("abd").unwrap());
+
+        assert_eq!(id, "abc");
+        assert_eq!(id, *"abc");
+        assert_eq!(id, String::from("abc"));
+        assert_eq!(id, MStr::new_owned("abc"));
+        assert_eq!("abc", id);
+        assert_eq!(*"abc", id);
+        assert_eq!(String::from("abc"), id);
+        assert_eq!(MStr::new_owned("abc"), id);
+    }
+
+    #[test]
+    fn map_lookup() {
+        let mut set = alloc::collections::BTreeSet::new();
+        set.insert(Ident::new("abc").unwrap());
+
+        assert!(set.contains("abc"));
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Ident<'_>>();
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde_impls {
+        use super::*;
+        use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+        #[test]
+        fn basic() {
+            assert_tokens(&Ident::new("abc").unwrap(), &[Token::Str("abc")]);
+
+            let id: Ident<'static> = serde_json::from_str("\"abc\"").unwrap();
+            assert!(id.as_mstr().is_owned());
+        }
+
+        #[test]
+        fn de_validates() {
+            assert_de_tokens_error::<Ident<'_>>(
+                &[Token::Str("a-b")],
+                "invalid identifier character at 1",
+            );
+        }
+    }
+}
@@ -0,0 +1,800 @@
+use crate::MStr;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::ptr::NonNull;
+use core::{fmt, mem, ptr, slice, str};
+
+// the high bit of len
+// if set (1), the data is borrowed (rest.ptr points to an &'a str)
+// if not (0), the data is owned (inline if len <= INLINE_LEN, otherwise rest.ptr points to a Box<str>)
+const BORROWED: u32 = 1 << 31;
+// every bit except the tag bit
+const LEN_MASK: u32 = !BORROWED;
+
+// the most bytes that can be stored inline (prefix + rest)
+const INLINE_LEN: usize = 12;
+
+/// A 16-byte maybe-owned string that keeps the first 4 bytes inline, for fast comparisons.
+///
+/// This is an "Umbra-style" (or "German") string:
+/// the length and a 4-byte prefix of the string are stored inline,
+/// so most equality checks and orderings are decided without following the pointer.
+/// Owned strings of up to 12 bytes are stored entirely inline, and never allocate.
+///
+/// Just like [`MStr`], a `PrefixMStr` is either borrowed (pointing to an `&'a str`) or owned.
+/// Borrowed strings are never copied, no matter how short.
+///
+/// Because the length is stored in 31 bits,
+/// a `PrefixMStr` can hold at most [`PrefixMStr::MAX_LEN`] bytes (2 GiB).
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::PrefixMStr;
+/// let a = PrefixMStr::new_owned("tiny");
+/// let b = PrefixMStr::new_borrowed("a string that is much longer");
+///
+/// assert!(a.is_inline());
+/// assert!(b.is_borrowed());
+/// assert!(a > b); // decided by the prefix alone
+/// assert_eq!(std::mem::size_of::<PrefixMStr<'_>>(), 16);
+/// ```
+#[repr(C)]
+pub struct PrefixMStr<'a> {
+    // if high bit (BORROWED) is set, we are borrowed
+    len: u32,
+
+    // the first 4 bytes of the string, zero padded
+    prefix: [u8; 4],
+
+    rest: Rest,
+
+    // use the lifetime (also makes it covariant)
+    _marker: PhantomData<&'a str>,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+union Rest {
+    // bytes 4..12 of an inline string, zero padded
+    inline: [u8; 8],
+    // the start of a borrowed or heap allocated string
+    ptr: NonNull<u8>,
+}
+
+unsafe impl Send for PrefixMStr<'_> {}
+unsafe impl Sync for PrefixMStr<'_> {}
+
+impl<'a> PrefixMStr<'a> {
+    /// The maximum length (in bytes) of a `PrefixMStr`.
+    pub const MAX_LEN: usize = LEN_MASK as usize;
+
+    // -- Constructors --
+
+    /// Creates a new `PrefixMStr<'a>` from an `&'a str`.
+    ///
+    /// The returned `PrefixMStr` is borrowed for the same lifetime as the input data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s` is longer than [`MAX_LEN`](PrefixMStr::MAX_LEN).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::PrefixMStr;
+    /// let s = "foo";
+    /// let pstr = PrefixMStr::new_borrowed(s);
+    ///
+    /// assert!(pstr.is_borrowed());
+    /// assert_eq!(pstr, "foo");
+    /// assert_eq!(pstr.as_ptr(), s.as_ptr());
+    /// ```
+    #[must_use]
+    pub const fn new_borrowed(s: &'a str) -> PrefixMStr<'a> {
+        let len = check_len(s.len());
+
+        PrefixMStr {
+            len: len | BORROWED,
+            prefix: prefix_of(s.as_bytes()),
+            rest: Rest {
+                // SAFETY: a str's pointer is never null
+                ptr: unsafe { NonNull::new_unchecked(s.as_ptr().cast_mut()) },
+            },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a new `PrefixMStr` from owned data.
+    /// The input type is anything that can be converted into a `Box<str>` (String, &str, etc).
+    ///
+    /// The returned `PrefixMStr` is owned.
+    /// If the string is at most 12 bytes long, it is copied inline (and the box is freed).
+    /// Otherwise the allocation is reused, just like [`MStr::new_owned`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s` is longer than [`MAX_LEN`](PrefixMStr::MAX_LEN).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::PrefixMStr;
+    /// let short = PrefixMStr::new_owned("short");
+    /// let long = PrefixMStr::new_owned("a bit longer than 12 bytes");
+    ///
+    /// assert!(short.is_owned() && short.is_inline());
+    /// assert!(long.is_owned() && !long.is_inline());
+    /// ```
+    #[must_use]
+    pub fn new_owned(s: impl Into<Box<str>>) -> PrefixMStr<'a> {
+        let s = s.into();
+
+        if s.len() <= INLINE_LEN {
+            return PrefixMStr::new_inline(&s);
+        }
+
+        let len = check_len(s.len());
+        let prefix = prefix_of(s.as_bytes());
+        let ptr = Box::into_raw(s).cast::<u8>();
+
+        PrefixMStr {
+            len,
+            prefix,
+            rest: Rest {
+                // SAFETY: Box::into_raw is never null
+                ptr: unsafe { NonNull::new_unchecked(ptr) },
+            },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a new `PrefixMStr<'a>` from an `MStr<'a>`.
+    ///
+    /// The returned `PrefixMStr` will be borrowed if the `MStr` is borrowed,
+    /// and owned if the `MStr` is owned (see [`new_owned`](PrefixMStr::new_owned)).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s` is longer than [`MAX_LEN`](PrefixMStr::MAX_LEN).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::{MStr, PrefixMStr};
+    /// assert!(PrefixMStr::new(MStr::new_borrowed("foo")).is_borrowed());
+    /// assert!(PrefixMStr::new(MStr::new_owned("foo")).is_owned());
+    /// ```
+    #[must_use]
+    pub fn new(s: MStr<'a>) -> PrefixMStr<'a> {
+        match s.as_borrowed() {
+            Some(s) => PrefixMStr::new_borrowed(s),
+            None => PrefixMStr::new_owned(s.into_boxed()),
+        }
+    }
+
+    fn new_inline(s: &str) -> PrefixMStr<'a> {
+        debug_assert!(s.len() <= INLINE_LEN);
+
+        let mut bytes = [0; INLINE_LEN];
+        bytes[..s.len()].copy_from_slice(s.as_bytes());
+
+        let mut prefix = [0; 4];
+        let mut inline = [0; 8];
+        prefix.copy_from_slice(&bytes[..4]);
+        inline.copy_from_slice(&bytes[4..]);
+
+        PrefixMStr {
+            len: s.len() as u32,
+            prefix,
+            rest: Rest { inline },
+            _marker: PhantomData,
+        }
+    }
+
+    // -- Accessors --
+
+    /// Converts this `PrefixMStr` to a string slice.
+    #[inline]
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the bytes always come from a valid str
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    /// Converts this `PrefixMStr` to a UTF-8 byte slice.
+    #[inline]
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len()) }
+    }
+
+    /// Gets a pointer (`*const u8`) to the underlying string's buffer.
+    ///
+    /// For inline strings, this points into `self`.
+    ///
+    /// Do **NOT** use the returned pointer mutably, as `self` may be borrowed.
+    #[inline]
+    #[must_use]
+    pub fn as_ptr(&self) -> *const u8 {
+        if self.is_inline() {
+            // the inline bytes are prefix followed by rest (repr(C)),
+            // so derive the pointer from all of self, not just the prefix field
+            let this = ptr::addr_of!(*self).cast::<u8>();
+            unsafe { this.add(mem::size_of::<u32>()) }
+        } else {
+            unsafe { self.rest.ptr.as_ptr() }
+        }
+    }
+
+    /// Gets the first 4 bytes of this string, padded with zeros if it is shorter.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::PrefixMStr;
+    /// assert_eq!(PrefixMStr::new_borrowed("hello").prefix(), *b"hell");
+    /// assert_eq!(PrefixMStr::new_borrowed("hi").prefix(), *b"hi\0\0");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn prefix(&self) -> [u8; 4] {
+        self.prefix
+    }
+
+    /// Gets the length of the underlying string.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        (self.len & LEN_MASK) as usize
+    }
+
+    /// Checks if the underlying string is empty (length of 0).
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if this `PrefixMStr` is borrowed.
+    ///
+    /// Exactly one of `is_borrowed` and `is_owned` will be true for every `PrefixMStr`.
+    #[inline]
+    #[must_use]
+    pub const fn is_borrowed(&self) -> bool {
+        self.len & BORROWED == BORROWED
+    }
+
+    /// Checks if this `PrefixMStr` is owned (either inline or on the heap).
+    ///
+    /// Exactly one of `is_borrowed` and `is_owned` will be true for every `PrefixMStr`.
+    #[inline]
+    #[must_use]
+    pub const fn is_owned(&self) -> bool {
+        !self.is_borrowed()
+    }
+
+    /// Checks if this `PrefixMStr` is owned and stored entirely inline (without a heap allocation).
+    ///
+    /// This is the case for every owned string of up to 12 bytes.
+    #[inline]
+    #[must_use]
+    pub const fn is_inline(&self) -> bool {
+        // a borrowed len always has the high bit set, so is never <= INLINE_LEN
+        self.len as usize <= INLINE_LEN
+    }
+
+    /// If this `PrefixMStr<'a>` is borrowed, get the underlying `&'a str`.
+    ///
+    /// This will return `Some` if `self` is borrowed, and `None` if `self` is owned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::PrefixMStr;
+    /// assert_eq!(PrefixMStr::new_borrowed("abc").as_borrowed(), Some("abc"));
+    /// assert_eq!(PrefixMStr::new_owned("abc").as_borrowed(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn as_borrowed(&self) -> Option<&'a str> {
+        if self.is_borrowed() {
+            // SAFETY: self is borrowed which means it is an &'a str
+            Some(unsafe { &*self.as_str_ptr() })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn as_str_ptr(&self) -> *const str {
+        ptr::slice_from_raw_parts(self.as_ptr(), self.len()) as *const str
+    }
+
+    // -- Conversions --
+
+    /// Converts this `PrefixMStr<'a>` into an `MStr<'a>`.
+    /// This will consume `self`.
+    ///
+    /// If `self` is borrowed, the returned `MStr` is borrowed.
+    /// If `self` is owned, the returned `MStr` is owned,
+    /// reusing the allocation if there is one (inline strings are copied to the heap).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::PrefixMStr;
+    /// assert!(PrefixMStr::new_borrowed("foo").into_mstr().is_borrowed());
+    /// assert!(PrefixMStr::new_owned("foo").into_mstr().is_owned());
+    /// ```
+    #[must_use]
+    pub fn into_mstr(self) -> MStr<'a> {
+        if let Some(s) = self.as_borrowed() {
+            MStr::new_borrowed(s)
+        } else if self.is_inline() {
+            MStr::new_owned(self.as_str())
+        } else {
+            let ptr = self.as_str_ptr();
+            mem::forget(self);
+            // SAFETY: self is owned, and not inline, so ptr came from a Box<str>
+            MStr::new_owned(unsafe { Box::from_raw(ptr.cast_mut()) })
+        }
+    }
+
+    /// Converts this `PrefixMStr` into an owned `String`.
+    /// This will consume `self`.
+    #[inline]
+    #[must_use]
+    pub fn into_string(self) -> String {
+        self.into_mstr().into_string()
+    }
+
+    // the first 8 bytes (len without the tag, then the prefix) as a single integer,
+    // to compare both at once
+    #[inline]
+    fn len_and_prefix(&self) -> u64 {
+        (self.len & LEN_MASK) as u64 | (u32::from_ne_bytes(self.prefix) as u64) << 32
+    }
+}
+
+#[inline]
+const fn check_len(len: usize) -> u32 {
+    if len > PrefixMStr::MAX_LEN {
+        panic!("string too long for PrefixMStr");
+    }
+    len as u32
+}
+
+#[inline]
+const fn prefix_of(s: &[u8]) -> [u8; 4] {
+    let mut prefix = [0; 4];
+    let mut i = 0;
+    while i < 4 && i < s.len() {
+        prefix[i] = s[i];
+        i += 1;
+    }
+    prefix
+}
+
+// ===== Trait Impls =====
+
+impl Clone for PrefixMStr<'_> {
+    /// Clones this `PrefixMStr`.
+    ///
+    /// Borrowed and inline strings are copied without allocating,
+    /// and heap strings are copied to a new allocation.
+    fn clone(&self) -> Self {
+        if self.is_borrowed() || self.is_inline() {
+            PrefixMStr {
+                len: self.len,
+                prefix: self.prefix,
+                rest: self.rest,
+                _marker: PhantomData,
+            }
+        } else {
+            PrefixMStr::new_owned(self.as_str())
+        }
+    }
+}
+
+impl Drop for PrefixMStr<'_> {
+    fn drop(&mut self) {
+        if !self.is_borrowed() && !self.is_inline() {
+            let b = unsafe { Box::from_raw(self.as_str_ptr().cast_mut()) };
+            drop(b);
+        }
+    }
+}
+
+impl Default for PrefixMStr<'_> {
+    /// Creates a new, empty, owned (inline) `PrefixMStr`.
+    fn default() -> Self {
+        PrefixMStr::new_inline("")
+    }
+}
+
+// -- Format --
+
+impl Debug for PrefixMStr<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Display for PrefixMStr<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+// -- Convert From --
+
+impl<'a> From<MStr<'a>> for PrefixMStr<'a> {
+    fn from(value: MStr<'a>) -> Self {
+        PrefixMStr::new(value)
+    }
+}
+
+impl<'a> From<&'a str> for PrefixMStr<'a> {
+    fn from(value: &'a str) -> Self {
+        PrefixMStr::new_borrowed(value)
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for PrefixMStr<'a> {
+    fn from(value: Cow<'a, str>) -> Self {
+        PrefixMStr::new(MStr::new_cow(value))
+    }
+}
+
+impl From<String> for PrefixMStr<'_> {
+    fn from(value: String) -> Self {
+        PrefixMStr::new_owned(value)
+    }
+}
+
+impl From<Box<str>> for PrefixMStr<'_> {
+    fn from(value: Box<str>) -> Self {
+        PrefixMStr::new_owned(value)
+    }
+}
+
+// -- Convert To --
+
+impl<'a> From<PrefixMStr<'a>> for MStr<'a> {
+    fn from(value: PrefixMStr<'a>) -> Self {
+        value.into_mstr()
+    }
+}
+
+impl From<PrefixMStr<'_>> for String {
+    fn from(value: PrefixMStr<'_>) -> Self {
+        value.into_string()
+    }
+}
+
+// -- Convert Ref --
+
+impl Deref for PrefixMStr<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for PrefixMStr<'_> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for PrefixMStr<'_> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+// -- Hash --
+
+impl Hash for PrefixMStr<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // must match str's hash, because of Borrow<str>
+        Hash::hash(self.as_str(), state)
+    }
+}
+
+// -- [Partial]Eq --
+
+impl Eq for PrefixMStr<'_> {}
+
+impl PartialEq for PrefixMStr<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        // different lengths or prefixes can never be equal
+        if self.len_and_prefix() != other.len_and_prefix() {
+            return false;
+        }
+        // the prefix is the whole string
+        if self.len() <= 4 {
+            return true;
+        }
+        if self.is_inline() && other.is_inline() {
+            // SAFETY: both are inline, and the unused inline bytes are always zero
+            return unsafe { self.rest.inline == other.rest.inline };
+        }
+        self.as_bytes()[4..] == other.as_bytes()[4..]
+    }
+}
+
+impl PartialEq<str> for PrefixMStr<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<PrefixMStr<'_>> for str {
+    fn eq(&self, other: &PrefixMStr<'_>) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<&str> for PrefixMStr<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<PrefixMStr<'_>> for &str {
+    fn eq(&self, other: &PrefixMStr<'_>) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<MStr<'_>> for PrefixMStr<'_> {
+    fn eq(&self, other: &MStr<'_>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<PrefixMStr<'_>> for MStr<'_> {
+    fn eq(&self, other: &PrefixMStr<'_>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+// -- [Partial]Ord --
+
+impl Ord for PrefixMStr<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // prefixes are zero padded, and zero is the smallest byte,
+        // so comparing them as big-endian integers matches comparing the strings
+        // (unless they are equal, in which case the rest of the string decides)
+        let a = u32::from_be_bytes(self.prefix);
+        let b = u32::from_be_bytes(other.prefix);
+
+        match a.cmp(&b) {
+            Ordering::Equal => self.as_bytes().cmp(other.as_bytes()),
+            ord => ord,
+        }
+    }
+}
+
+impl PartialOrd for PrefixMStr<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialOrd<str> for PrefixMStr<'_> {
+    fn partial_cmp(&self, other: &str) -> Option<Ordering> {
+        self.as_str().partial_cmp(other)
+    }
+}
+
+impl PartialOrd<PrefixMStr<'_>> for str {
+    fn partial_cmp(&self, other: &PrefixMStr<'_>) -> Option<Ordering> {
+        self.partial_cmp(other.as_str())
+    }
+}
+
+// ===== serde =====
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::*;
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, Serializer};
+
+    impl Serialize for PrefixMStr<'_> {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_str(self.as_str())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for PrefixMStr<'_> {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            MStr::deserialize(d).map(PrefixMStr::new)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use serde_test::{assert_tokens, Token};
+
+        #[test]
+        fn basic() {
+            assert_tokens(&PrefixMStr::from("roar"), &[Token::Str("roar")]);
+            assert_tokens(
+                &PrefixMStr::from("a much longer string"),
+                &[Token::String("a much longer string")],
+            );
+        }
+    }
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    const STRINGS: [&str; 12] = [
+        "",
+        "\0",
+        "a",
+        "a\0",
+        "ab",
+        "abcd",
+        "abcde",
+        "abcdefghijkl",
+        "abcdefghijklm",
+        "abcdefghijklz",
+        "b",
+        "🐸🐸🐸🐸",
+    ];
+
+    fn all_reprs(s: &str) -> [PrefixMStr<'_>; 2] {
+        [PrefixMStr::new_borrowed(s), PrefixMStr::new_owned(s)]
+    }
+
+    #[test]
+    fn size() {
+        assert_eq!(mem::size_of::<PrefixMStr<'_>>(), 16);
+    }
+
+    #[test]
+    fn correct_repr() {
+        let b = PrefixMStr::new_borrowed("abc");
+        assert!(b.is_borrowed() && !b.is_owned() && !b.is_inline());
+
+        let i = PrefixMStr::new_owned("abcdefghijkl");
+        assert!(i.is_owned() && !i.is_borrowed() && i.is_inline());
+
+        let h = PrefixMStr::new_owned("abcdefghijklm");
+        assert!(h.is_owned() && !h.is_borrowed() && !h.is_inline());
+
+        assert!(PrefixMStr::default().is_inline());
+        assert!(PrefixMStr::default().is_empty());
+    }
+
+    #[test]
+    fn roundtrip() {
+        for s in STRINGS {
+            for p in all_reprs(s) {
+                assert_eq!(p.as_str(), s);
+                assert_eq!(p.len(), s.len());
+                assert_eq!(p.clone().as_str(), s);
+                assert_eq!(p.into_string(), s);
+            }
+        }
+    }
+
+    #[test]
+    fn borrowed_stays_borrowed() {
+        let s = String::from("hi");
+        let p = PrefixMStr::new_borrowed(&s);
+
+        assert_eq!(p.as_ptr(), s.as_ptr());
+        assert_eq!(p.clone().as_ptr(), s.as_ptr());
+
+        let m = p.into_mstr();
+        assert!(m.is_borrowed());
+        assert_eq!(m.as_ptr(), s.as_ptr());
+    }
+
+    #[test]
+    fn owned_reuses_alloc() {
+        let s = String::from("this one is long enough");
+        let ptr = s.as_ptr();
+        let p = PrefixMStr::new(MStr::new_owned(s));
+
+        assert_eq!(p.as_ptr(), ptr);
+        assert_eq!(p.into_mstr().as_ptr(), ptr);
+    }
+
+    #[test]
+    fn inline_points_into_self() {
+        let p = PrefixMStr::new_owned("inline");
+        let start = &p as *const PrefixMStr<'_> as usize;
+        let ptr = p.as_ptr() as usize;
+
+        assert!(ptr > start && ptr < start + 16);
+    }
+
+    #[test]
+    fn eq_matches_str() {
+        for a in STRINGS {
+            for b in STRINGS {
+                for pa in all_reprs(a) {
+                    for pb in all_reprs(b) {
+                        assert_eq!(pa == pb, a == b, "{a:?} == {b:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ord_matches_str() {
+        for a in STRINGS {
+            for b in STRINGS {
+                for pa in all_reprs(a) {
+                    for pb in all_reprs(b) {
+                        assert_eq!(pa.cmp(&pb), a.cmp(b), "{a:?} cmp {b:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sort() {
+        let mut sorted = STRINGS.to_vec();
+        sorted.sort();
+
+        let mut prefixed = STRINGS
+            .iter()
+            .rev()
+            .map(|s| PrefixMStr::new_owned(*s))
+            .collect::<Vec<_>>();
+        prefixed.sort();
+
+        assert_eq!(prefixed, sorted.as_slice());
+    }
+
+    #[test]
+    fn cross_eq() {
+        let p = PrefixMStr::new_owned("foo");
+
+        assert_eq!(p, "foo");
+        assert_eq!(p, *"foo");
+        assert_eq!(p, MStr::new_borrowed("foo"));
+        assert_eq!(MStr::new_borrowed("foo"), p);
+        assert!(p < *"fop");
+    }
+
+    #[test]
+    fn static_lt() {
+        let owned: PrefixMStr<'static> = PrefixMStr::new_owned("abc");
+        let borrowed: PrefixMStr<'static> = PrefixMStr::new_borrowed("abc");
+
+        assert_eq!(owned, borrowed);
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<PrefixMStr<'_>>();
+        assert_send_sync::<PrefixMStr<'static>>();
+    }
+}
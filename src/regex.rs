@@ -0,0 +1,436 @@
+//! Searching and replacing in an `MStr` with [regex](https://docs.rs/regex).
+//!
+//! [`MStr::replace_regex`] (and [`replace_all_regex`](MStr::replace_all_regex)) return the string itself
+//! (borrowed or owned) when nothing matches, like [`Regex::replace`] does with `Cow::Borrowed`.
+//!
+//! The search methods ([`find_regex`](MStr::find_regex), [`captures_regex`](MStr::captures_regex), ...)
+//! return matches as `MStr<'a>`s with the lifetime of the original string, instead of the lifetime of `&*mstr`.
+//! Matches in a borrowed string are borrowed from the same `&'a str`, so they can outlive the `MStr` they came from.
+//! Matches in an owned string are copied.
+//!
+//! Requires the `regex` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use regex::Regex;
+//!
+//! let re = Regex::new(r"(?<key>\w+)=(?<value>\w+)").unwrap();
+//!
+//! fn value<'a>(re: &Regex, line: MStr<'a>) -> Option<MStr<'a>> {
+//!     line.captures_regex(re)?.name("value")
+//! }
+//!
+//! let config = String::from("mode=fast");
+//! let v = value(&re, MStr::new_borrowed(&config)).unwrap();
+//! assert_eq!(v, "fast");
+//! assert!(v.is_borrowed());
+//! ```
+
+use crate::MStr;
+use ::regex::{Regex, Replacer};
+use alloc::borrow::Cow;
+use core::fmt::{self, Debug, Formatter};
+use core::iter::FusedIterator;
+
+// ===== Replace =====
+
+impl<'a> MStr<'a> {
+    /// Replaces the first match of `re` with `rep`, like [`Regex::replace`].
+    ///
+    /// If there is no match, `self` is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// use regex::Regex;
+    ///
+    /// let re = Regex::new(r"(\w+)@(\w+)").unwrap();
+    /// let s = MStr::new_borrowed("mail user@host now").replace_regex(&re, "$2!$1");
+    /// assert_eq!(s, "mail host!user now");
+    ///
+    /// let s = MStr::new_borrowed("no mail").replace_regex(&re, "$2!$1");
+    /// assert!(s.is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn replace_regex(self, re: &Regex, rep: impl Replacer) -> MStr<'a> {
+        self.replacen_regex(re, 1, rep)
+    }
+
+    /// Replaces every (non-overlapping) match of `re` with `rep`, like [`Regex::replace_all`].
+    ///
+    /// If there are no matches, `self` is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// use regex::Regex;
+    ///
+    /// let re = Regex::new(r"\s+").unwrap();
+    /// let s = MStr::new_owned("lots   of\t\tspace").replace_all_regex(&re, " ");
+    /// assert_eq!(s, "lots of space");
+    /// ```
+    #[must_use]
+    pub fn replace_all_regex(self, re: &Regex, rep: impl Replacer) -> MStr<'a> {
+        self.replacen_regex(re, 0, rep)
+    }
+
+    /// Replaces the first `limit` matches of `re` with `rep`, like [`Regex::replacen`].
+    ///
+    /// If `limit` is `0`, every match is replaced.
+    /// If there are no matches, `self` is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// use regex::Regex;
+    ///
+    /// let re = Regex::new(r"\d").unwrap();
+    /// assert_eq!(MStr::new_borrowed("12345").replacen_regex(&re, 2, "#"), "##345");
+    /// ```
+    #[must_use]
+    pub fn replacen_regex(self, re: &Regex, limit: usize, rep: impl Replacer) -> MStr<'a> {
+        match re.replacen(&self, limit, rep) {
+            // only returned if nothing matched, and then it is all of `self`
+            Cow::Borrowed(_) => self,
+            Cow::Owned(s) => MStr::new_owned(s),
+        }
+    }
+}
+
+// ===== Search =====
+
+impl<'a> MStr<'a> {
+    /// Returns the first match of `re` in this string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// use regex::Regex;
+    ///
+    /// let re = Regex::new(r"\d+").unwrap();
+    /// let m = MStr::new_borrowed("route 66").find_regex(&re).unwrap();
+    /// assert_eq!(m, "66");
+    /// assert!(m.is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn find_regex(&self, re: &Regex) -> Option<MStr<'a>> {
+        let m = re.find(self)?;
+        Some(MStr::new_subslice(self.as_borrowed(), m.as_str()))
+    }
+
+    /// Returns an iterator over every (non-overlapping) match of `re` in this string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// use regex::Regex;
+    ///
+    /// let re = Regex::new(r"\d+").unwrap();
+    /// let s = MStr::new_borrowed("1, 22 and 333");
+    /// let numbers: Vec<_> = s.find_iter_regex(&re).collect();
+    /// assert_eq!(numbers, ["1", "22", "333"]);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn find_iter_regex<'r, 's>(&'s self, re: &'r Regex) -> Matches<'r, 's, 'a> {
+        Matches {
+            iter: re.find_iter(self),
+            src: self.as_borrowed(),
+        }
+    }
+
+    /// Returns the capture groups of the first match of `re` in this string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// use regex::Regex;
+    ///
+    /// let re = Regex::new(r"(\d{4})-(\d{2})-(?<day>\d{2})").unwrap();
+    /// let s = MStr::new_borrowed("on 2024-05-17");
+    /// let caps = s.captures_regex(&re).unwrap();
+    /// assert_eq!(caps.get(0).unwrap(), "2024-05-17");
+    /// assert_eq!(caps.get(1).unwrap(), "2024");
+    /// assert_eq!(caps.name("day").unwrap(), "17");
+    /// ```
+    #[must_use]
+    pub fn captures_regex<'s>(&'s self, re: &Regex) -> Option<Captures<'s, 'a>> {
+        Some(Captures {
+            caps: re.captures(self)?,
+            src: self.as_borrowed(),
+        })
+    }
+
+    /// Returns an iterator over the capture groups of every (non-overlapping) match of `re` in this string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// use regex::Regex;
+    ///
+    /// let re = Regex::new(r"(\w+)=(\w+)").unwrap();
+    /// let s = MStr::new_borrowed("a=1 b=2");
+    /// let pairs: Vec<_> = s
+    ///     .captures_iter_regex(&re)
+    ///     .map(|caps| (caps.get(1).unwrap(), caps.get(2).unwrap()))
+    ///     .collect();
+    /// assert_eq!(pairs, [("a", "1"), ("b", "2")].map(|(k, v)| (MStr::new_borrowed(k), MStr::new_borrowed(v))));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn captures_iter_regex<'r, 's>(&'s self, re: &'r Regex) -> CaptureMatches<'r, 's, 'a> {
+        CaptureMatches {
+            iter: re.captures_iter(self),
+            src: self.as_borrowed(),
+        }
+    }
+}
+
+// ===== Captures =====
+
+/// The capture groups of a single match, as `MStr<'a>`s.
+///
+/// Created by [`MStr::captures_regex`] and [`MStr::captures_iter_regex`].
+/// Wraps a [`regex::Captures`], which is available with [`inner`](Captures::inner).
+pub struct Captures<'s, 'a> {
+    caps: ::regex::Captures<'s>,
+    // the string, if it is borrowed
+    src: Option<&'a str>,
+}
+
+impl<'s, 'a> Captures<'s, 'a> {
+    /// Returns capture group `i`, or `None` if it didn't participate in the match.
+    ///
+    /// Group `0` is the whole match.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, i: usize) -> Option<MStr<'a>> {
+        let m = self.caps.get(i)?;
+        Some(MStr::new_subslice(self.src, m.as_str()))
+    }
+
+    /// Returns the capture group called `name`, or `None` if it didn't participate in the match (or doesn't exist).
+    #[inline]
+    #[must_use]
+    pub fn name(&self, name: &str) -> Option<MStr<'a>> {
+        let m = self.caps.name(name)?;
+        Some(MStr::new_subslice(self.src, m.as_str()))
+    }
+
+    /// Returns the number of capture groups (including group `0`), whether they participated in the match or not.
+    // there is always group `0`, so an `is_empty` would always be `false`
+    #[allow(clippy::len_without_is_empty)]
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.caps.len()
+    }
+
+    /// Returns an iterator over every capture group (including group `0`), in order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Option<MStr<'a>>> + '_ {
+        let src = self.src;
+        self.caps
+            .iter()
+            .map(move |m| Some(MStr::new_subslice(src, m?.as_str())))
+    }
+
+    /// Returns the wrapped [`regex::Captures`].
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &::regex::Captures<'s> {
+        &self.caps
+    }
+}
+
+impl Debug for Captures<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.caps, f)
+    }
+}
+
+// ===== Iterators =====
+
+/// An iterator over the matches of a regex in an [`MStr`].
+///
+/// Created by [`MStr::find_iter_regex`].
+#[derive(Debug)]
+pub struct Matches<'r, 's, 'a> {
+    iter: ::regex::Matches<'r, 's>,
+    src: Option<&'a str>,
+}
+
+impl<'a> Iterator for Matches<'_, '_, 'a> {
+    type Item = MStr<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<MStr<'a>> {
+        let m = self.iter.next()?;
+        Some(MStr::new_subslice(self.src, m.as_str()))
+    }
+}
+
+impl FusedIterator for Matches<'_, '_, '_> {}
+
+/// An iterator over the capture groups of the matches of a regex in an [`MStr`].
+///
+/// Created by [`MStr::captures_iter_regex`].
+#[derive(Debug)]
+pub struct CaptureMatches<'r, 's, 'a> {
+    iter: ::regex::CaptureMatches<'r, 's>,
+    src: Option<&'a str>,
+}
+
+impl<'s, 'a> Iterator for CaptureMatches<'_, 's, 'a> {
+    type Item = Captures<'s, 'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Captures<'s, 'a>> {
+        Some(Captures {
+            caps: self.iter.next()?,
+            src: self.src,
+        })
+    }
+}
+
+impl FusedIterator for CaptureMatches<'_, '_, '_> {}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    fn re(pattern: &str) -> Regex {
+        Regex::new(pattern).unwrap()
+    }
+
+    #[test]
+    fn replace_no_match() {
+        let re = re(r"\d+");
+        for f in [
+            |s: MStr<'static>, re: &Regex| s.replace_regex(re, "#"),
+            |s: MStr<'static>, re: &Regex| s.replace_all_regex(re, "#"),
+            |s: MStr<'static>, re: &Regex| s.replacen_regex(re, 3, "#"),
+        ] {
+            let input = "no digits";
+            let s = f(MStr::new_borrowed(input), &re);
+            assert!(s.is_borrowed());
+            assert_eq!(s.as_ptr(), input.as_ptr());
+
+            let owned = MStr::new_owned(input);
+            let ptr = owned.as_ptr();
+            let s = f(owned, &re);
+            assert!(s.is_owned());
+            assert_eq!(s.as_ptr(), ptr);
+        }
+    }
+
+    #[test]
+    fn replace() {
+        let re = re(r"(?<n>\d+)");
+        let s = MStr::new_borrowed("a1 b22 c333");
+        assert_eq!(s.clone().replace_regex(&re, "<$n>"), "a<1> b22 c333");
+        assert_eq!(
+            s.clone().replace_all_regex(&re, "<$n>"),
+            "a<1> b<22> c<333>"
+        );
+        assert_eq!(s.clone().replacen_regex(&re, 2, "#"), "a# b# c333");
+        assert_eq!(
+            s.replace_all_regex(&re, |caps: &::regex::Captures<'_>| caps[1]
+                .len()
+                .to_string()),
+            "a1 b2 c3"
+        );
+
+        // an empty match still counts as a change
+        let s = MStr::new_borrowed("").replace_regex(&self::re("^"), "");
+        assert!(s.is_owned());
+    }
+
+    #[test]
+    fn find_borrowed() {
+        let input = alloc::string::String::from("x=1, y=22");
+        let (m, all) = {
+            // the matches outlive the `MStr`
+            let s = MStr::new_borrowed(&input);
+            let re = re(r"\d+");
+            (
+                s.find_regex(&re).unwrap(),
+                s.find_iter_regex(&re).collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(m, "1");
+        assert!(m.is_borrowed());
+        assert_eq!(m.as_ptr(), input[2..].as_ptr());
+        assert_eq!(all, ["1", "22"]);
+        assert!(all.iter().all(MStr::is_borrowed));
+
+        assert_eq!(MStr::new_borrowed("none").find_regex(&re(r"\d")), None);
+    }
+
+    #[test]
+    fn find_owned() {
+        let s = MStr::new_owned("x=1, y=22");
+        let re = re(r"\d+");
+        let m = s.find_regex(&re).unwrap();
+        assert_eq!(m, "1");
+        assert!(m.is_owned());
+
+        let all: Vec<_> = s.find_iter_regex(&re).collect();
+        assert_eq!(all, ["1", "22"]);
+        assert!(all.iter().all(MStr::is_owned));
+    }
+
+    #[test]
+    fn captures() {
+        let re = re(r"(?<key>\w+)(?:=(?<value>\w+))?");
+        let input = "flag key=value";
+
+        for s in [MStr::new_borrowed(input), MStr::new_owned(input)] {
+            let all: Vec<_> = s.captures_iter_regex(&re).collect();
+            assert_eq!(all.len(), 2);
+
+            let caps = &all[0];
+            assert_eq!(caps.len(), 3);
+            assert_eq!(caps.get(0).unwrap(), "flag");
+            assert_eq!(caps.name("key").unwrap(), "flag");
+            assert_eq!(caps.name("value"), None);
+            assert_eq!(caps.name("missing"), None);
+            assert_eq!(caps.get(3), None);
+
+            let caps = &all[1];
+            assert_eq!(
+                caps.iter().collect::<Vec<_>>(),
+                [Some("key=value"), Some("key"), Some("value")].map(|m| m.map(MStr::new_borrowed))
+            );
+            assert_eq!(caps.inner()["value"], *"value");
+            assert_eq!(caps.get(2).unwrap().is_borrowed(), s.is_borrowed());
+
+            let first = s.captures_regex(&re).unwrap();
+            assert_eq!(first.get(1).unwrap(), "flag");
+        }
+
+        assert!(MStr::new_borrowed("!!").captures_regex(&re).is_none());
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Captures<'_, '_>>();
+        assert_send_sync::<Matches<'_, '_, '_>>();
+        assert_send_sync::<CaptureMatches<'_, '_, '_>>();
+    }
+}
@@ -0,0 +1,383 @@
+//! Splitting an `MStr` into grapheme clusters, words and sentences.
+//!
+//! These use [unicode-segmentation](https://docs.rs/unicode-segmentation),
+//! but unlike its iterators (over `&*mstr`), the pieces are `MStr<'a>`s with the lifetime of the original string.
+//! Pieces of a borrowed string are borrowed from the same `&'a str`,
+//! so they can outlive the `MStr` they came from.
+//! Pieces of an owned string are copied.
+//!
+//! Requires the `segmentation` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! fn first_word<'a>(s: &MStr<'a>) -> Option<MStr<'a>> {
+//!     s.words().next()
+//! }
+//!
+//! let text = String::from("¡Hola, mundo!");
+//! let word = first_word(&MStr::new_borrowed(&text)); // the MStr is dropped here
+//! assert_eq!(word.unwrap(), "Hola");
+//! ```
+
+use crate::MStr;
+use core::fmt::{self, Debug, Formatter};
+use core::iter::{self, FusedIterator};
+use core::ops::{Bound, RangeBounds};
+use unicode_segmentation::UnicodeSegmentation;
+
+// ===== MStr Methods =====
+
+impl<'a> MStr<'a> {
+    /// Returns an iterator over the (extended) grapheme clusters of this string.
+    ///
+    /// A grapheme cluster is what a user would think of as a single character,
+    /// like a letter with its accents, or an emoji sequence.
+    ///
+    /// This is named differently from [`UnicodeSegmentation::graphemes`] (which `MStr` gets through `str`),
+    /// so that enabling this feature doesn't change what `mstr.graphemes(true)` returns.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("cafe\u{301} 🇯🇵");
+    /// let graphemes: Vec<_> = s.grapheme_clusters().collect();
+    /// assert_eq!(graphemes, ["c", "a", "f", "e\u{301}", " ", "🇯🇵"]);
+    /// assert!(graphemes.iter().all(MStr::is_borrowed));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn grapheme_clusters(&self) -> Graphemes<'_, 'a> {
+        Graphemes {
+            iter: self.as_str().graphemes(true),
+            src: self.as_borrowed(),
+        }
+    }
+
+    /// Returns an iterator over the words of this string.
+    ///
+    /// Words are split on Unicode word boundaries, like [`UnicodeSegmentation::unicode_words`],
+    /// so whitespace and punctuation between words are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("The quick (\"brown\") fox can't jump 32.3 feet, right?");
+    /// let words: Vec<_> = s.words().collect();
+    /// assert_eq!(words, ["The", "quick", "brown", "fox", "can't", "jump", "32.3", "feet", "right"]);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn words(&self) -> Words<'_, 'a> {
+        Words {
+            iter: self.as_str().unicode_words(),
+            src: self.as_borrowed(),
+        }
+    }
+
+    /// Returns an iterator over the sentences of this string.
+    ///
+    /// Sentences are split on Unicode sentence boundaries, like [`UnicodeSegmentation::unicode_sentences`].
+    /// Each sentence includes its trailing whitespace, and pieces without any letters or digits are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("Mr. Fox jumped. [...] The dog was too lazy.");
+    /// let sentences: Vec<_> = s.sentences().collect();
+    /// assert_eq!(sentences, ["Mr. ", "Fox jumped. ", "The dog was too lazy."]);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn sentences(&self) -> Sentences<'_, 'a> {
+        Sentences {
+            iter: self.as_str().unicode_sentences(),
+            src: self.as_borrowed(),
+        }
+    }
+
+    /// Returns the grapheme clusters in `range` (counted in grapheme clusters, not bytes).
+    ///
+    /// Returns `None` if the range is out of bounds, or its start is after its end.
+    ///
+    /// Borrowed strings give a borrowed slice, and owned strings give an owned copy of the slice.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("🇯🇵 cafe\u{301}!");
+    /// assert_eq!(s.grapheme_slice(2..6).unwrap(), "cafe\u{301}");
+    /// assert_eq!(s.grapheme_slice(..1).unwrap(), "🇯🇵");
+    /// assert_eq!(s.grapheme_slice(7..).unwrap(), "");
+    ///
+    /// assert_eq!(s.grapheme_slice(..8), None);
+    /// ```
+    #[must_use]
+    pub fn grapheme_slice(&self, range: impl RangeBounds<usize>) -> Option<MStr<'a>> {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.checked_add(1)?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => Some(n.checked_add(1)?),
+            Bound::Excluded(&n) => Some(n),
+            Bound::Unbounded => None,
+        };
+
+        let s = self.as_str();
+        let len = match end {
+            Some(end) => Some(end.checked_sub(start)?),
+            None => None,
+        };
+
+        let start = grapheme_offset(s, start)?;
+        let end = match len {
+            // counting from a grapheme boundary finds the same boundaries as counting from the start
+            Some(len) => start + grapheme_offset(&s[start..], len)?,
+            None => s.len(),
+        };
+        Some(MStr::new_subslice(self.as_borrowed(), &s[start..end]))
+    }
+}
+
+// the byte offset of the boundary before the `n`th grapheme cluster (or the end)
+fn grapheme_offset(s: &str, n: usize) -> Option<usize> {
+    s.grapheme_indices(true)
+        .map(|(i, _)| i)
+        .chain(iter::once(s.len()))
+        .nth(n)
+}
+
+// ===== Iterators =====
+
+/// An iterator over the grapheme clusters of an [`MStr`].
+///
+/// Created by [`MStr::grapheme_clusters`].
+#[derive(Clone)]
+pub struct Graphemes<'s, 'a> {
+    iter: unicode_segmentation::Graphemes<'s>,
+    // the string, if it is borrowed
+    src: Option<&'a str>,
+}
+
+impl<'a> Iterator for Graphemes<'_, 'a> {
+    type Item = MStr<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<MStr<'a>> {
+        self.iter.next().map(|g| MStr::new_subslice(self.src, g))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Graphemes<'_, '_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next_back()
+            .map(|g| MStr::new_subslice(self.src, g))
+    }
+}
+
+impl FusedIterator for Graphemes<'_, '_> {}
+
+impl Debug for Graphemes<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Graphemes")
+            .field(&self.iter.as_str())
+            .finish()
+    }
+}
+
+/// An iterator over the words of an [`MStr`].
+///
+/// Created by [`MStr::words`].
+pub struct Words<'s, 'a> {
+    iter: unicode_segmentation::UnicodeWords<'s>,
+    src: Option<&'a str>,
+}
+
+impl<'a> Iterator for Words<'_, 'a> {
+    type Item = MStr<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<MStr<'a>> {
+        self.iter.next().map(|w| MStr::new_subslice(self.src, w))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Words<'_, '_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next_back()
+            .map(|w| MStr::new_subslice(self.src, w))
+    }
+}
+
+impl FusedIterator for Words<'_, '_> {}
+
+impl Debug for Words<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Words").finish_non_exhaustive()
+    }
+}
+
+/// An iterator over the sentences of an [`MStr`].
+///
+/// Created by [`MStr::sentences`].
+#[derive(Clone)]
+pub struct Sentences<'s, 'a> {
+    iter: unicode_segmentation::UnicodeSentences<'s>,
+    src: Option<&'a str>,
+}
+
+impl<'a> Iterator for Sentences<'_, 'a> {
+    type Item = MStr<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<MStr<'a>> {
+        self.iter.next().map(|s| MStr::new_subslice(self.src, s))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl FusedIterator for Sentences<'_, '_> {}
+
+impl Debug for Sentences<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sentences").finish_non_exhaustive()
+    }
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    // the pieces outlive the `MStr` they came from
+    fn collect_words(s: &str) -> Vec<MStr<'_>> {
+        let s = MStr::new_borrowed(s);
+        s.words().collect()
+    }
+
+    #[test]
+    fn borrowed_pieces() {
+        let input = "Hello, wörld! e\u{301}";
+        let s = MStr::new_borrowed(input);
+
+        let graphemes: Vec<_> = s.grapheme_clusters().collect();
+        assert_eq!(graphemes.concat(), input);
+        for g in &graphemes {
+            assert!(g.is_borrowed());
+            let range = input.as_ptr() as usize..input.as_ptr() as usize + input.len();
+            assert!(range.contains(&(g.as_ptr() as usize)));
+        }
+        assert_eq!(graphemes.last().unwrap(), "e\u{301}");
+
+        let words = collect_words(input);
+        assert_eq!(words, ["Hello", "wörld", "e\u{301}"]);
+        assert!(words.iter().all(MStr::is_borrowed));
+        assert_eq!(words[1].as_ptr(), input[7..].as_ptr());
+    }
+
+    #[test]
+    fn owned_pieces() {
+        let s = MStr::new_owned("One. Two!");
+
+        let graphemes: Vec<_> = s.grapheme_clusters().collect();
+        assert_eq!(graphemes.len(), 9);
+        assert!(graphemes.iter().all(MStr::is_owned));
+
+        let sentences: Vec<_> = s.sentences().collect();
+        assert_eq!(sentences, ["One. ", "Two!"]);
+        assert!(sentences.iter().all(MStr::is_owned));
+    }
+
+    #[test]
+    fn reversed() {
+        let s = MStr::new_borrowed("a👨\u{200D}👩\u{200D}👧🇯🇵🇫🇷");
+        let graphemes: Vec<_> = s.grapheme_clusters().rev().collect();
+        assert_eq!(graphemes, ["🇫🇷", "🇯🇵", "👨\u{200D}👩\u{200D}👧", "a"]);
+
+        let words: Vec<_> = MStr::new_borrowed("one two three").words().rev().collect();
+        assert_eq!(words, ["three", "two", "one"]);
+    }
+
+    #[test]
+    fn trait_methods_not_shadowed() {
+        let s = MStr::new_borrowed("e\u{301}x");
+        let graphemes: unicode_segmentation::Graphemes<'_> = s.graphemes(false);
+        assert_eq!(graphemes.collect::<Vec<_>>(), ["e\u{301}", "x"]);
+    }
+
+    #[test]
+    fn empty() {
+        let s = MStr::new_borrowed("");
+        assert_eq!(s.grapheme_clusters().next(), None);
+        assert_eq!(s.words().next(), None);
+        assert_eq!(s.sentences().next(), None);
+        assert_eq!(MStr::new_borrowed(" ... ").words().next(), None);
+    }
+
+    #[test]
+    fn grapheme_slice() {
+        let input = "🇯🇵🇫🇷e\u{301}x";
+        for s in [MStr::new_borrowed(input), MStr::new_owned(input)] {
+            assert_eq!(s.grapheme_slice(..).unwrap(), input);
+            assert_eq!(s.grapheme_slice(0..0).unwrap(), "");
+            assert_eq!(s.grapheme_slice(1..2).unwrap(), "🇫🇷");
+            assert_eq!(s.grapheme_slice(1..=2).unwrap(), "🇫🇷e\u{301}");
+            assert_eq!(s.grapheme_slice(2..).unwrap(), "e\u{301}x");
+            assert_eq!(s.grapheme_slice(4..).unwrap(), "");
+            assert_eq!(
+                s.grapheme_slice((Bound::Excluded(0), Bound::Unbounded))
+                    .unwrap(),
+                "🇫🇷e\u{301}x"
+            );
+
+            assert_eq!(s.grapheme_slice(5..), None);
+            assert_eq!(s.grapheme_slice(..5), None);
+            assert_eq!(s.grapheme_slice(..=4), None);
+            assert_eq!(s.grapheme_slice(..=usize::MAX), None);
+            #[allow(clippy::reversed_empty_ranges)]
+            let reversed = s.grapheme_slice(2..1);
+            assert_eq!(reversed, None);
+        }
+
+        let s = MStr::new_borrowed(input);
+        let slice = s.grapheme_slice(2..3).unwrap();
+        assert!(slice.is_borrowed());
+        assert_eq!(slice.as_ptr(), input[16..].as_ptr());
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Graphemes<'_, '_>>();
+        assert_send_sync::<Words<'_, '_>>();
+        assert_send_sync::<Sentences<'_, '_>>();
+    }
+}
@@ -0,0 +1,447 @@
+//! POSIX shell word splitting and quoting.
+//!
+//! [`split`] splits a command line into words the way a POSIX shell would,
+//! handling quotes, backslashes and comments, but not expansions (`$VAR`, `*`, `~`, ...).
+//! [`quote`] and [`join`] do the reverse, quoting only the words that need it.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use mstr::shell;
+//!
+//! let argv = shell::split(MStr::new_borrowed(r#"cc -o 'my app' "src/$name.c" # build"#)).unwrap();
+//! assert_eq!(argv, ["cc", "-o", "my app", "src/$name.c"]);
+//!
+//! // plain words borrow the input
+//! assert!(argv[0].is_borrowed());
+//!
+//! assert_eq!(shell::join(&argv), "cc -o 'my app' 'src/$name.c'");
+//! ```
+
+use crate::MStr;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+// ===== Split =====
+
+/// Splits `s` into words, like a POSIX shell.
+///
+/// - Words are separated by spaces, tabs and newlines.
+/// - Inside single quotes, every character is literal.
+/// - Inside double quotes, a backslash only escapes `$`, `` ` ``, `"`, `\` and newlines.
+/// - Outside quotes, a backslash escapes any character.
+/// - A backslash before a newline removes both (a line continuation).
+/// - A `#` at the start of a word starts a comment, which runs to the end of the line.
+///
+/// Nothing is expanded, so `$`, `*`, `~` and friends are kept as-is.
+///
+/// If `s` is borrowed, every word that is a single piece of the input
+/// (like `word`, `'quoted word'` or `"quoted word"`) borrows from it.
+/// Other words are owned.
+///
+/// # Errors
+///
+/// Returns an error with the byte offset of the problem
+/// if a quote is never closed, or if `s` ends with a backslash.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::shell::{self, ErrorKind};
+///
+/// let words = shell::split(MStr::new_borrowed(r#"echo "it's" a\ b ''"#)).unwrap();
+/// assert_eq!(words, ["echo", "it's", "a b", ""]);
+/// assert!(words[1].is_borrowed() && words[2].is_owned());
+///
+/// let err = shell::split(MStr::new_borrowed("echo 'oops")).unwrap_err();
+/// assert_eq!(err.offset(), 5);
+/// assert_eq!(err.kind(), ErrorKind::UnclosedQuote);
+/// ```
+pub fn split(s: MStr<'_>) -> Result<Vec<MStr<'_>>, SplitError> {
+    let bytes = s.as_bytes();
+    let mut words = Vec::new();
+    // the word being built, if there is one
+    let mut word: Option<Word> = None;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b' ' | b'\t' | b'\n' => {
+                if let Some(word) = word.take() {
+                    words.push(word.finish(&s));
+                }
+                i += 1;
+            }
+            b'#' if word.is_none() => {
+                i = s[i..].find('\n').map_or(s.len(), |n| i + n);
+            }
+            b'\'' => {
+                let Some(len) = s[i + 1..].find('\'') else {
+                    return Err(SplitError {
+                        offset: i,
+                        kind: ErrorKind::UnclosedQuote,
+                    });
+                };
+
+                let end = i + 1 + len;
+                word.get_or_insert(Word::Empty).push(&s, i + 1, end);
+                i = end + 1;
+            }
+            b'"' => {
+                let word = word.get_or_insert(Word::Empty);
+
+                // the start of the part that hasn't been pushed yet
+                let mut start = i + 1;
+                let mut j = start;
+                loop {
+                    match bytes.get(j) {
+                        Some(b'"') => break,
+                        Some(b'\\') => match bytes.get(j + 1) {
+                            Some(b'$' | b'`' | b'"' | b'\\') => {
+                                // drop the backslash, keep the escaped char
+                                word.push(&s, start, j);
+                                start = j + 1;
+                                j += 2;
+                            }
+                            Some(b'\n') => {
+                                word.push(&s, start, j);
+                                start = j + 2;
+                                j += 2;
+                            }
+                            _ => j += 1,
+                        },
+                        Some(_) => j += 1,
+                        None => {
+                            return Err(SplitError {
+                                offset: i,
+                                kind: ErrorKind::UnclosedQuote,
+                            })
+                        }
+                    }
+                }
+
+                word.push(&s, start, j);
+                i = j + 1;
+            }
+            b'\\' => match s[i + 1..].chars().next() {
+                // a line continuation doesn't start a word
+                Some('\n') => i += 2,
+                Some(c) => {
+                    let end = i + 1 + c.len_utf8();
+                    word.get_or_insert(Word::Empty).push(&s, i + 1, end);
+                    i = end;
+                }
+                None => {
+                    return Err(SplitError {
+                        offset: i,
+                        kind: ErrorKind::TrailingBackslash,
+                    })
+                }
+            },
+            _ => {
+                // everything up to the next special character is literal
+                let len = bytes[i..]
+                    .iter()
+                    .position(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\'' | b'"' | b'\\'))
+                    .unwrap_or(bytes.len() - i);
+
+                word.get_or_insert(Word::Empty).push(&s, i, i + len);
+                i += len;
+            }
+        }
+    }
+
+    if let Some(word) = word {
+        words.push(word.finish(&s));
+    }
+    Ok(words)
+}
+
+// a word being built out of pieces of the input
+enum Word {
+    Empty,
+    // a single contiguous piece, which can be borrowed
+    Slice(usize, usize),
+    Owned(String),
+}
+
+impl Word {
+    // adds `s[start..end]` to the end of the word
+    fn push(&mut self, s: &str, start: usize, end: usize) {
+        match self {
+            Word::Empty => *self = Word::Slice(start, end),
+            Word::Slice(_, prev_end) if *prev_end == start => *prev_end = end,
+            Word::Slice(prev_start, prev_end) => {
+                let mut owned = String::with_capacity(*prev_end - *prev_start + end - start);
+                owned.push_str(&s[*prev_start..*prev_end]);
+                owned.push_str(&s[start..end]);
+                *self = Word::Owned(owned);
+            }
+            Word::Owned(owned) => owned.push_str(&s[start..end]),
+        }
+    }
+
+    fn finish<'a>(self, s: &MStr<'a>) -> MStr<'a> {
+        match self {
+            Word::Empty => MStr::new_borrowed(""),
+            Word::Slice(start, end) => match s.as_borrowed() {
+                Some(s) => MStr::new_borrowed(&s[start..end]),
+                None => MStr::new_owned(&s[start..end]),
+            },
+            Word::Owned(owned) => MStr::new_owned(owned),
+        }
+    }
+}
+
+// ===== Quote =====
+
+/// Quotes `s` so that a POSIX shell reads it as a single word.
+///
+/// Words made only of ASCII letters, digits and `_ @ % + = : , . / -` are returned unchanged.
+/// Anything else is wrapped in single quotes, with any `'` written as `'\''`.
+/// The empty string becomes `''`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::shell;
+///
+/// assert!(shell::quote(MStr::new_borrowed("--out=dist/app.tar.gz")).is_borrowed());
+/// assert_eq!(shell::quote(MStr::new_borrowed("my file")), "'my file'");
+/// assert_eq!(shell::quote(MStr::new_borrowed("it's")), r"'it'\''s'");
+/// assert_eq!(shell::quote(MStr::new_borrowed("")), "''");
+/// ```
+#[must_use]
+pub fn quote(s: MStr<'_>) -> MStr<'_> {
+    if s.is_empty() {
+        return MStr::new_borrowed("''");
+    }
+    if s.bytes().all(is_safe) {
+        return s;
+    }
+
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('\'');
+    for (i, part) in s.split('\'').enumerate() {
+        if i > 0 {
+            out.push_str("'\\''");
+        }
+        out.push_str(part);
+    }
+    out.push('\'');
+
+    MStr::new_owned(out)
+}
+
+/// Quotes every word with [`quote`], and joins them with spaces.
+///
+/// [`split`] turns the result back into the same words.
+///
+/// # Examples
+///
+/// ```rust
+/// use mstr::shell;
+///
+/// assert_eq!(shell::join(["git", "commit", "-m", "fix: don't panic"]), r"git commit -m 'fix: don'\''t panic'");
+/// ```
+#[must_use]
+pub fn join<I>(words: I) -> String
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut out = String::new();
+    for word in words {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(&quote(MStr::new_borrowed(word.as_ref())));
+    }
+    out
+}
+
+// characters that never need quoting
+#[inline]
+fn is_safe(b: u8) -> bool {
+    b.is_ascii_alphanumeric()
+        || matches!(
+            b,
+            b'_' | b'@' | b'%' | b'+' | b'=' | b':' | b',' | b'.' | b'/' | b'-'
+        )
+}
+
+// ===== Error =====
+
+/// The error returned by [`split`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SplitError {
+    offset: usize,
+    kind: ErrorKind,
+}
+
+/// The kind of a [`SplitError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A single or double quote that is never closed.
+    UnclosedQuote,
+    /// A backslash at the very end of the input, with nothing to escape.
+    TrailingBackslash,
+}
+
+impl SplitError {
+    /// Returns the byte offset of the unclosed quote, or of the trailing backslash.
+    #[inline]
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns what was wrong with the input.
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl Display for SplitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let msg = match self.kind {
+            ErrorKind::UnclosedQuote => "unclosed quote",
+            ErrorKind::TrailingBackslash => "trailing backslash",
+        };
+        write!(f, "{msg} at offset {}", self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SplitError {}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split_str(s: &str) -> Result<Vec<MStr<'_>>, SplitError> {
+        split(MStr::new_borrowed(s))
+    }
+
+    fn err(offset: usize, kind: ErrorKind) -> SplitError {
+        SplitError { offset, kind }
+    }
+
+    #[test]
+    fn split_words() {
+        assert_eq!(split_str("").unwrap(), [""; 0]);
+        assert_eq!(split_str(" \t\n ").unwrap(), [""; 0]);
+        assert_eq!(split_str("a  b\tc\nd").unwrap(), ["a", "b", "c", "d"]);
+        assert_eq!(
+            split_str("  🐸 $HOME *.rs ~ ").unwrap(),
+            ["🐸", "$HOME", "*.rs", "~"]
+        );
+    }
+
+    #[test]
+    fn split_quotes() {
+        assert_eq!(split_str(r#"'a b' "c d""#).unwrap(), ["a b", "c d"]);
+        assert_eq!(split_str(r#"'' """#).unwrap(), ["", ""]);
+        assert_eq!(split_str(r#"a'b'"c"d"#).unwrap(), ["abcd"]);
+        assert_eq!(split_str(r#"'"' "'" '\n'"#).unwrap(), ["\"", "'", "\\n"]);
+        assert_eq!(
+            split_str(r#""\$ \` \" \\ \n \a""#).unwrap(),
+            [r#"$ ` " \ \n \a"#]
+        );
+        assert_eq!(split_str("\"a\\\nb\"").unwrap(), ["ab"]);
+    }
+
+    #[test]
+    fn split_backslashes() {
+        assert_eq!(
+            split_str(r"a\ b \'c\' \\ \é").unwrap(),
+            ["a b", "'c'", "\\", "é"]
+        );
+        // line continuations
+        assert_eq!(split_str("a\\\nb \\\n c").unwrap(), ["ab", "c"]);
+    }
+
+    #[test]
+    fn split_comments() {
+        assert_eq!(split_str("a # b c\nd").unwrap(), ["a", "d"]);
+        assert_eq!(split_str("# only a comment").unwrap(), [""; 0]);
+        // only at the start of a word
+        assert_eq!(split_str(r"a#b '#' \# #").unwrap(), ["a#b", "#", "#"]);
+    }
+
+    #[test]
+    fn split_borrows() {
+        let input = r#"plain 'single' "double" mi'x'ed "esc\"aped" \x"#;
+        let words = split_str(input).unwrap();
+        assert_eq!(
+            words,
+            ["plain", "single", "double", "mixed", "esc\"aped", "x"]
+        );
+
+        let borrowed: Vec<bool> = words.iter().map(MStr::is_borrowed).collect();
+        assert_eq!(borrowed, [true, true, true, false, false, true]);
+        assert_eq!(words[0].as_ptr(), input.as_ptr());
+
+        // owned input is copied
+        let words = split(MStr::new_owned("a 'b c'")).unwrap();
+        assert_eq!(words, ["a", "b c"]);
+        assert!(words.iter().all(MStr::is_owned));
+    }
+
+    #[test]
+    fn split_errors() {
+        assert_eq!(split_str("a 'b"), Err(err(2, ErrorKind::UnclosedQuote)));
+        assert_eq!(
+            split_str(r#"a "b\""#),
+            Err(err(2, ErrorKind::UnclosedQuote))
+        );
+        assert_eq!(split_str(r#"'"' ""#), Err(err(4, ErrorKind::UnclosedQuote)));
+        assert_eq!(split_str("a \\"), Err(err(2, ErrorKind::TrailingBackslash)));
+
+        assert_eq!(
+            alloc::format!("{}", err(2, ErrorKind::UnclosedQuote)),
+            "unclosed quote at offset 2"
+        );
+    }
+
+    #[test]
+    fn quote_words() {
+        assert!(quote(MStr::new_borrowed("AZaz09_@%+=:,./-")).is_borrowed());
+        assert_eq!(quote(MStr::new_borrowed("a b")), "'a b'");
+        assert_eq!(quote(MStr::new_borrowed("$x")), "'$x'");
+        assert_eq!(quote(MStr::new_borrowed("é")), "'é'");
+        assert_eq!(quote(MStr::new_borrowed("'")), r"''\'''");
+        assert_eq!(quote(MStr::new_borrowed("")), "''");
+
+        let owned = MStr::new_owned("safe");
+        let ptr = owned.as_ptr();
+        assert_eq!(quote(owned).as_ptr(), ptr);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let words = [
+            "plain",
+            "",
+            "a b",
+            "it's",
+            "\"double\"",
+            "back\\slash",
+            "#hash",
+            "new\nline",
+            "$HOME",
+            "🐸",
+        ];
+        let joined = join(words);
+        assert_eq!(split(MStr::new_borrowed(&joined)).unwrap(), words);
+    }
+}
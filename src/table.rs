@@ -0,0 +1,632 @@
+//! A compact binary "string table" format, with zero-copy loading.
+//!
+//! [`write()`] packs a list of strings into a single buffer,
+//! and [`StringTable::load`] validates such a buffer once,
+//! after which every lookup returns a borrowed [`MStr`] without allocating.
+//!
+//! # Format
+//!
+//! All integers are little-endian.
+//!
+//! | Field      | Size              | Description                                          |
+//! |------------|-------------------|------------------------------------------------------|
+//! | `magic`    | 4                 | always `b"MSTB"`                                     |
+//! | `version`  | 2                 | format version, currently [`VERSION`]                |
+//! | `flags`    | 2                 | reserved, must be zero                               |
+//! | `count`    | 4                 | number of strings                                    |
+//! | `data_len` | 4                 | length of `data` in bytes                            |
+//! | `checksum` | 4                 | CRC-32 (IEEE) of `ends` followed by `data`           |
+//! | `ends`     | 4 &times; `count` | end offset of each string within `data`              |
+//! | `data`     | `data_len`        | every string, back-to-back, as UTF-8                 |
+//!
+//! The `i`th string spans `ends[i - 1]..ends[i]` in `data` (with `ends[-1]` being `0`).
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! # use mstr::table::{self, StringTable};
+//! let buf = table::write(["foo", "bar", "baz"]).unwrap();
+//! let table = StringTable::load(&buf).unwrap();
+//!
+//! assert_eq!(table.len(), 3);
+//!
+//! let s: MStr<'_> = table.get(1).unwrap();
+//! assert!(s.is_borrowed());
+//! assert_eq!(s, "bar");
+//! ```
+
+use crate::MStr;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter};
+use core::iter::FusedIterator;
+use core::{fmt, str};
+
+/// The magic bytes at the start of every string table.
+pub const MAGIC: [u8; 4] = *b"MSTB";
+
+/// The current version of the string table format.
+///
+/// Tables with any other version are rejected by [`StringTable::load`].
+pub const VERSION: u16 = 1;
+
+const HEADER_LEN: usize = 20;
+
+// ===== Writing =====
+
+/// Writes a list of strings to a new string table buffer.
+///
+/// The strings can be anything that implements `AsRef<str>`, such as `MStr`, `&str`, or `String`.
+///
+/// Returns [`TableError::TooLarge`] if there are more than `u32::MAX` strings,
+/// or if their combined length is more than `u32::MAX` bytes.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// # use mstr::table::{self, StringTable};
+/// let strings = [MStr::new_borrowed("foo"), MStr::new_owned("bar")];
+/// let buf = table::write(&strings).unwrap();
+///
+/// let table = StringTable::load(&buf).unwrap();
+/// assert!(table.iter().eq(strings));
+/// ```
+pub fn write<I>(strings: I) -> Result<Vec<u8>, TableError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut ends = Vec::new();
+    let mut data = Vec::new();
+
+    for s in strings {
+        data.extend_from_slice(s.as_ref().as_bytes());
+        let end = u32::try_from(data.len()).map_err(|_| TableError::TooLarge)?;
+        ends.extend_from_slice(&end.to_le_bytes());
+    }
+
+    let count = u32::try_from(ends.len() / 4).map_err(|_| TableError::TooLarge)?;
+    let data_len = u32::try_from(data.len()).map_err(|_| TableError::TooLarge)?;
+    let checksum = Crc32::new().update(&ends).update(&data).finish();
+
+    let mut buf = Vec::with_capacity(HEADER_LEN + ends.len() + data.len());
+    buf.extend_from_slice(&MAGIC);
+    buf.extend_from_slice(&VERSION.to_le_bytes());
+    buf.extend_from_slice(&0u16.to_le_bytes());
+    buf.extend_from_slice(&count.to_le_bytes());
+    buf.extend_from_slice(&data_len.to_le_bytes());
+    buf.extend_from_slice(&checksum.to_le_bytes());
+    buf.extend_from_slice(&ends);
+    buf.extend_from_slice(&data);
+
+    Ok(buf)
+}
+
+// ===== Loading =====
+
+/// A validated, borrowed view of a string table buffer.
+///
+/// Created by [`StringTable::load`], which validates the entire buffer up front,
+/// so lookups never allocate or fail.
+#[derive(Clone, Copy)]
+pub struct StringTable<'buf> {
+    ends: &'buf [u8],
+    data: &'buf str,
+}
+
+impl<'buf> StringTable<'buf> {
+    /// Validates a string table buffer, and returns a view of it.
+    ///
+    /// This checks the header, the checksum, that every offset is in bounds and in order,
+    /// and that the string data is valid UTF-8 and split on char boundaries.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::table::{self, StringTable, TableError};
+    /// let mut buf = table::write(["foo"]).unwrap();
+    /// assert!(StringTable::load(&buf).is_ok());
+    ///
+    /// *buf.last_mut().unwrap() = b'x';
+    /// assert!(matches!(
+    ///     StringTable::load(&buf),
+    ///     Err(TableError::ChecksumMismatch { .. })
+    /// ));
+    /// ```
+    pub fn load(buf: &'buf [u8]) -> Result<StringTable<'buf>, TableError> {
+        if buf.len() < HEADER_LEN {
+            return Err(TableError::Truncated);
+        }
+        if buf[0..4] != MAGIC {
+            return Err(TableError::BadMagic);
+        }
+
+        let version = read_u16(&buf[4..]);
+        if version != VERSION {
+            return Err(TableError::UnsupportedVersion(version));
+        }
+        if read_u16(&buf[6..]) != 0 {
+            return Err(TableError::UnsupportedFlags);
+        }
+
+        let count = read_u32(&buf[8..]) as usize;
+        let data_len = read_u32(&buf[12..]) as usize;
+        let expected = read_u32(&buf[16..]);
+
+        let body = &buf[HEADER_LEN..];
+        let ends_len = count.checked_mul(4).ok_or(TableError::Truncated)?;
+        match ends_len.checked_add(data_len) {
+            Some(len) if len == body.len() => {}
+            Some(len) if len < body.len() => return Err(TableError::TrailingBytes),
+            _ => return Err(TableError::Truncated),
+        }
+
+        let (ends, data) = body.split_at(ends_len);
+
+        let found = Crc32::new().update(ends).update(data).finish();
+        if found != expected {
+            return Err(TableError::ChecksumMismatch { expected, found });
+        }
+
+        let data = str::from_utf8(data).map_err(|_| TableError::InvalidUtf8)?;
+
+        let mut start = 0;
+        for (index, end) in ends.chunks_exact(4).map(read_u32).enumerate() {
+            let end = end as usize;
+            if end < start || end > data.len() || !data.is_char_boundary(end) {
+                return Err(TableError::InvalidOffset { index });
+            }
+            start = end;
+        }
+        if start != data.len() {
+            return Err(TableError::TrailingBytes);
+        }
+
+        Ok(StringTable { ends, data })
+    }
+
+    /// Gets the number of strings in this table.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ends.len() / 4
+    }
+
+    /// Checks if this table contains no strings.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Gets the string at `index` as a borrowed `MStr`, or `None` if it is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::table::{self, StringTable};
+    /// let buf = table::write(["foo", "bar"]).unwrap();
+    /// let table = StringTable::load(&buf).unwrap();
+    ///
+    /// assert_eq!(table.get(0).unwrap(), "foo");
+    /// assert!(table.get(0).unwrap().is_borrowed());
+    /// assert_eq!(table.get(2), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<MStr<'buf>> {
+        self.get_str(index).map(MStr::new_borrowed)
+    }
+
+    /// Gets the string at `index` as a string slice, or `None` if it is out of bounds.
+    #[must_use]
+    pub fn get_str(&self, index: usize) -> Option<&'buf str> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let start = match index {
+            0 => 0,
+            _ => self.end(index - 1),
+        };
+        let end = self.end(index);
+
+        // SAFETY: every offset was checked to be an in-order char boundary by load
+        Some(unsafe { self.data.get_unchecked(start..end) })
+    }
+
+    /// Returns an iterator over every string in this table, as borrowed `MStr`s.
+    #[inline]
+    pub fn iter(&self) -> Iter<'buf> {
+        Iter {
+            table: *self,
+            front: 0,
+            back: self.len(),
+        }
+    }
+
+    #[inline]
+    fn end(&self, index: usize) -> usize {
+        read_u32(&self.ends[index * 4..]) as usize
+    }
+}
+
+impl Debug for StringTable<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'buf> IntoIterator for StringTable<'buf> {
+    type Item = MStr<'buf>;
+    type IntoIter = Iter<'buf>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the strings in a [`StringTable`].
+///
+/// Created by [`StringTable::iter`].
+#[derive(Debug, Clone)]
+pub struct Iter<'buf> {
+    table: StringTable<'buf>,
+    front: usize,
+    back: usize,
+}
+
+impl<'buf> Iterator for Iter<'buf> {
+    type Item = MStr<'buf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        self.table.get(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.table.get(self.back)
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+// ===== Errors =====
+
+/// An error produced when writing or loading a string table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TableError {
+    /// There were too many strings, or too much string data, to fit in a table.
+    TooLarge,
+    /// The buffer ended before the end of the table.
+    Truncated,
+    /// The buffer continued after the end of the table.
+    TrailingBytes,
+    /// The buffer doesn't start with [`MAGIC`].
+    BadMagic,
+    /// The table was written with an unknown format version.
+    UnsupportedVersion(u16),
+    /// The table has reserved flags set.
+    UnsupportedFlags,
+    /// The table's contents don't match its checksum.
+    ChecksumMismatch {
+        /// The checksum stored in the header.
+        expected: u32,
+        /// The checksum of the actual contents.
+        found: u32,
+    },
+    /// The string data is not valid UTF-8.
+    InvalidUtf8,
+    /// The end offset of the string at `index` is out of bounds, out of order,
+    /// or not on a char boundary.
+    InvalidOffset {
+        /// The index of the offending string.
+        index: usize,
+    },
+}
+
+impl Display for TableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::TooLarge => f.write_str("string table too large"),
+            TableError::Truncated => f.write_str("string table truncated"),
+            TableError::TrailingBytes => f.write_str("trailing bytes after string table"),
+            TableError::BadMagic => f.write_str("not a string table (bad magic)"),
+            TableError::UnsupportedVersion(v) => {
+                write!(f, "unsupported string table version {v}")
+            }
+            TableError::UnsupportedFlags => f.write_str("unsupported string table flags"),
+            TableError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "string table checksum mismatch (expected {expected:#010x}, found {found:#010x})"
+            ),
+            TableError::InvalidUtf8 => f.write_str("string table data is not valid UTF-8"),
+            TableError::InvalidOffset { index } => {
+                write!(f, "invalid offset for string {index} in string table")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TableError {}
+
+// ===== Helpers =====
+
+#[inline]
+fn read_u16(b: &[u8]) -> u16 {
+    u16::from_le_bytes([b[0], b[1]])
+}
+
+#[inline]
+fn read_u32(b: &[u8]) -> u32 {
+    u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+}
+
+// CRC-32 (IEEE 802.3), the same one used by zip, png, etc
+struct Crc32(u32);
+
+impl Crc32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut c = i as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = if c & 1 == 1 {
+                    0xEDB8_8320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                };
+                k += 1;
+            }
+            table[i] = c;
+            i += 1;
+        }
+        table
+    };
+
+    fn new() -> Crc32 {
+        Crc32(!0)
+    }
+
+    fn update(mut self, bytes: &[u8]) -> Crc32 {
+        for &b in bytes {
+            self.0 = Crc32::TABLE[((self.0 ^ b as u32) & 0xFF) as usize] ^ (self.0 >> 8);
+        }
+        self
+    }
+
+    fn finish(self) -> u32 {
+        !self.0
+    }
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec;
+
+    // rewrites the checksum so that tests can corrupt other parts of the table
+    fn fix_checksum(buf: &mut [u8]) {
+        let checksum = Crc32::new().update(&buf[HEADER_LEN..]).finish();
+        buf[16..20].copy_from_slice(&checksum.to_le_bytes());
+    }
+
+    #[test]
+    fn crc32() {
+        // the standard check value
+        assert_eq!(Crc32::new().update(b"123456789").finish(), 0xCBF4_3926);
+        assert_eq!(Crc32::new().finish(), 0);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let strings = ["", "foo", "", "bär", "🐸🐸", "a longer string with spaces"];
+        let buf = write(strings).unwrap();
+        let table = StringTable::load(&buf).unwrap();
+
+        assert_eq!(table.len(), strings.len());
+        assert!(table.iter().eq(strings));
+        assert!(table.iter().rev().eq(strings.iter().rev().copied()));
+        assert!(table.iter().all(|s| s.is_borrowed()));
+
+        for (i, s) in strings.iter().enumerate() {
+            assert_eq!(table.get_str(i), Some(*s));
+        }
+        assert_eq!(table.get(strings.len()), None);
+    }
+
+    #[test]
+    fn roundtrip_mstr() {
+        let strings = vec![MStr::new_borrowed("borrowed"), MStr::new_owned("owned")];
+        let buf = write(&strings).unwrap();
+        let table = StringTable::load(&buf).unwrap();
+
+        assert!(table.iter().eq(strings));
+    }
+
+    #[test]
+    fn empty() {
+        let buf = write(Vec::<String>::new()).unwrap();
+        assert_eq!(buf.len(), HEADER_LEN);
+
+        let table = StringTable::load(&buf).unwrap();
+        assert!(table.is_empty());
+        assert_eq!(table.get(0), None);
+        assert_eq!(table.iter().next(), None);
+    }
+
+    #[test]
+    fn borrows_from_buf() {
+        let buf = write(["abc"]).unwrap();
+        let s = {
+            let table = StringTable::load(&buf).unwrap();
+            table.get(0).unwrap()
+        };
+
+        assert_eq!(s.as_ptr(), buf[buf.len() - 3..].as_ptr());
+    }
+
+    #[test]
+    fn layout() {
+        let buf = write(["ab", "c"]).unwrap();
+
+        assert_eq!(&buf[0..4], b"MSTB");
+        assert_eq!(&buf[4..6], &[1, 0]);
+        assert_eq!(&buf[6..8], &[0, 0]);
+        assert_eq!(&buf[8..12], &[2, 0, 0, 0]);
+        assert_eq!(&buf[12..16], &[3, 0, 0, 0]);
+        assert_eq!(&buf[20..28], &[2, 0, 0, 0, 3, 0, 0, 0]);
+        assert_eq!(&buf[28..], b"abc");
+    }
+
+    #[test]
+    fn bad_header() {
+        let buf = write(["foo"]).unwrap();
+
+        assert_eq!(
+            StringTable::load(&buf[..10]).unwrap_err(),
+            TableError::Truncated
+        );
+        assert_eq!(StringTable::load(&[]).unwrap_err(), TableError::Truncated);
+
+        let mut b = buf.clone();
+        b[0] = b'X';
+        assert_eq!(StringTable::load(&b).unwrap_err(), TableError::BadMagic);
+
+        let mut b = buf.clone();
+        b[4] = 2;
+        assert_eq!(
+            StringTable::load(&b).unwrap_err(),
+            TableError::UnsupportedVersion(2)
+        );
+
+        let mut b = buf;
+        b[6] = 1;
+        assert_eq!(
+            StringTable::load(&b).unwrap_err(),
+            TableError::UnsupportedFlags
+        );
+    }
+
+    #[test]
+    fn bad_length() {
+        let buf = write(["foo", "bar"]).unwrap();
+
+        assert_eq!(
+            StringTable::load(&buf[..buf.len() - 1]).unwrap_err(),
+            TableError::Truncated
+        );
+
+        let mut b = buf.clone();
+        b.push(0);
+        assert_eq!(
+            StringTable::load(&b).unwrap_err(),
+            TableError::TrailingBytes
+        );
+
+        // huge count must not overflow
+        let mut b = buf;
+        b[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(StringTable::load(&b).unwrap_err(), TableError::Truncated);
+    }
+
+    #[test]
+    fn corrupt_checksum() {
+        let buf = write(["foo", "bar"]).unwrap();
+        let expected = read_u32(&buf[16..]);
+
+        let mut b = buf.clone();
+        *b.last_mut().unwrap() = b'z';
+        assert_eq!(
+            StringTable::load(&b).unwrap_err(),
+            TableError::ChecksumMismatch {
+                expected,
+                found: Crc32::new().update(&b[HEADER_LEN..]).finish(),
+            }
+        );
+
+        let mut b = buf;
+        b[20] = 1;
+        assert!(matches!(
+            StringTable::load(&b),
+            Err(TableError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut buf = write(["foo"]).unwrap();
+        *buf.last_mut().unwrap() = 0xFF;
+        fix_checksum(&mut buf);
+
+        assert_eq!(
+            StringTable::load(&buf).unwrap_err(),
+            TableError::InvalidUtf8
+        );
+    }
+
+    #[test]
+    fn invalid_offsets() {
+        let buf = write(["ab", "cd", "é"]).unwrap();
+
+        // out of order
+        let mut b = buf.clone();
+        b[24] = 1;
+        fix_checksum(&mut b);
+        assert_eq!(
+            StringTable::load(&b).unwrap_err(),
+            TableError::InvalidOffset { index: 1 }
+        );
+
+        // out of bounds
+        let mut b = buf.clone();
+        b[20] = 100;
+        fix_checksum(&mut b);
+        assert_eq!(
+            StringTable::load(&b).unwrap_err(),
+            TableError::InvalidOffset { index: 0 }
+        );
+
+        // not a char boundary (splits the é)
+        let mut b = buf.clone();
+        b[24] = 5;
+        fix_checksum(&mut b);
+        assert_eq!(
+            StringTable::load(&b).unwrap_err(),
+            TableError::InvalidOffset { index: 1 }
+        );
+
+        // last offset doesn't cover all of the data
+        let mut b = buf;
+        b[28] = 4;
+        fix_checksum(&mut b);
+        assert_eq!(
+            StringTable::load(&b).unwrap_err(),
+            TableError::TrailingBytes
+        );
+    }
+}
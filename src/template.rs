@@ -0,0 +1,580 @@
+//! `${name}` style string templates.
+//!
+//! A template is literal text with placeholders like `${name}` in it.
+//! `$$` is an escaped `$`, and any other `$` (not followed by `{`) is just a `$`.
+//!
+//! Placeholder names are made of ASCII letters, digits, `_`, `.` and `-`.
+//!
+//! Most templates (from config files, say) have no placeholders at all,
+//! and rendering one of those returns the template itself, without allocating.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use mstr::MStr;
+//! use mstr::template::{self, Template};
+//! use std::collections::BTreeMap;
+//!
+//! let vars = BTreeMap::from([("user", "ferris"), ("home", "/home/ferris")]);
+//!
+//! // parse once, render many times
+//! let t = Template::parse(MStr::new_borrowed("${home}/.config (for ${user})")).unwrap();
+//! assert_eq!(t.render(&vars).unwrap(), "/home/ferris/.config (for ferris)");
+//!
+//! // or in one go
+//! let s = template::render(MStr::new_borrowed("no placeholders"), |name| vars.get(name)).unwrap();
+//! assert!(s.is_borrowed());
+//! ```
+
+use crate::MStr;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::ops::Range;
+
+// ===== Template =====
+
+/// A parsed template, ready to be rendered.
+///
+/// See the [module docs](self) for the syntax.
+#[derive(Clone)]
+pub struct Template<'a> {
+    source: MStr<'a>,
+    parts: Vec<Part>,
+}
+
+#[derive(Clone)]
+enum Part {
+    Literal(Range<usize>),
+    Placeholder {
+        name: Range<usize>,
+        span: Range<usize>,
+    },
+}
+
+/// A piece of a [`Template`].
+///
+/// Returned by [`Template::segments`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Segment<'t> {
+    /// Literal text (with `$$` escapes already replaced).
+    Literal(&'t str),
+    /// A placeholder.
+    Placeholder {
+        /// The name of the placeholder, without the `${` and `}`.
+        name: &'t str,
+        /// The byte span of the whole placeholder (including the `${` and `}`) in the template.
+        span: Range<usize>,
+    },
+}
+
+impl<'a> Template<'a> {
+    /// Parses a template.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a placeholder is unclosed, empty, or has an invalid name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// use mstr::template::{ErrorKind, Template};
+    ///
+    /// let err = Template::parse(MStr::new_borrowed("a ${b c}")).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::InvalidName);
+    /// assert_eq!(err.span(), 2..8);
+    /// ```
+    pub fn parse(source: MStr<'a>) -> Result<Template<'a>, TemplateError> {
+        let parts = parse_parts(&source)?;
+        Ok(Template { source, parts })
+    }
+
+    /// Returns the template's source.
+    #[inline]
+    #[must_use]
+    pub fn source(&self) -> &MStr<'a> {
+        &self.source
+    }
+
+    /// Converts the template back into its source.
+    #[inline]
+    #[must_use]
+    pub fn into_source(self) -> MStr<'a> {
+        self.source
+    }
+
+    /// Returns `true` if the template has any placeholders.
+    #[inline]
+    #[must_use]
+    pub fn has_placeholders(&self) -> bool {
+        self.parts
+            .iter()
+            .any(|part| matches!(part, Part::Placeholder { .. }))
+    }
+
+    /// Returns the names of the template's placeholders, in order (including duplicates).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// # use mstr::template::Template;
+    /// let t = Template::parse(MStr::new_borrowed("${a} $${b} ${c} ${a}")).unwrap();
+    /// assert!(t.placeholders().eq(["a", "c", "a"]));
+    /// ```
+    pub fn placeholders(&self) -> impl Iterator<Item = &str> + '_ {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Literal(_) => None,
+            Part::Placeholder { name, .. } => Some(&self.source[name.clone()]),
+        })
+    }
+
+    /// Returns the template's literal text and placeholders, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// use mstr::template::{Segment, Template};
+    ///
+    /// let t = Template::parse(MStr::new_borrowed("$$${price}")).unwrap();
+    /// let segments: Vec<_> = t.segments().collect();
+    /// assert_eq!(
+    ///     segments,
+    ///     [
+    ///         Segment::Literal("$"),
+    ///         Segment::Placeholder { name: "price", span: 2..10 },
+    ///     ]
+    /// );
+    /// ```
+    pub fn segments(&self) -> impl Iterator<Item = Segment<'_>> + '_ {
+        self.parts.iter().map(|part| match part {
+            Part::Literal(range) => Segment::Literal(&self.source[range.clone()]),
+            Part::Placeholder { name, span } => Segment::Placeholder {
+                name: &self.source[name.clone()],
+                span: span.clone(),
+            },
+        })
+    }
+
+    /// Renders the template, looking up each placeholder in `vars`.
+    ///
+    /// If the template is all literal text (with no escapes), this returns (a clone of) the source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::UnknownPlaceholder`] error for the first placeholder that isn't in `vars`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// use mstr::template::{ErrorKind, Template};
+    /// use std::collections::BTreeMap;
+    ///
+    /// let t = Template::parse(MStr::new_borrowed("${greeting}, ${name}!")).unwrap();
+    ///
+    /// let mut vars = BTreeMap::new();
+    /// vars.insert("greeting", "Hello");
+    /// assert_eq!(t.render(&vars).unwrap_err().kind(), ErrorKind::UnknownPlaceholder);
+    ///
+    /// vars.insert("name", "world");
+    /// assert_eq!(t.render(&vars).unwrap(), "Hello, world!");
+    /// ```
+    pub fn render(&self, vars: &(impl Vars + ?Sized)) -> Result<MStr<'a>, TemplateError> {
+        self.render_with(|name| vars.get(name))
+    }
+
+    /// Renders the template, looking up each placeholder with `lookup`.
+    ///
+    /// If the template is all literal text (with no escapes), this returns (a clone of) the source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::UnknownPlaceholder`] error for the first placeholder `lookup` returns `None` for.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// # use mstr::template::Template;
+    /// let t = Template::parse(MStr::new_borrowed("${a} + ${b}")).unwrap();
+    /// let s = t.render_with(|name| Some(name.to_uppercase())).unwrap();
+    /// assert_eq!(s, "A + B");
+    /// ```
+    pub fn render_with<V: AsRef<str>>(
+        &self,
+        mut lookup: impl FnMut(&str) -> Option<V>,
+    ) -> Result<MStr<'a>, TemplateError> {
+        if self.is_source() {
+            return Ok(self.source.clone());
+        }
+
+        let mut out = String::with_capacity(self.source.len());
+        for part in &self.parts {
+            match part {
+                Part::Literal(range) => out.push_str(&self.source[range.clone()]),
+                Part::Placeholder { name, span } => {
+                    let Some(value) = lookup(&self.source[name.clone()]) else {
+                        return Err(TemplateError {
+                            start: span.start,
+                            end: span.end,
+                            kind: ErrorKind::UnknownPlaceholder,
+                        });
+                    };
+                    out.push_str(value.as_ref());
+                }
+            }
+        }
+        Ok(MStr::new_owned(out))
+    }
+
+    // whether rendering gives back the source unchanged
+    fn is_source(&self) -> bool {
+        match self.parts.as_slice() {
+            [] => true,
+            [Part::Literal(range)] => range.len() == self.source.len(),
+            _ => false,
+        }
+    }
+}
+
+impl Debug for Template<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Template").field(&self.source).finish()
+    }
+}
+
+/// Renders `template`, looking up each placeholder with `lookup`.
+///
+/// This is [`Template::parse`] followed by [`Template::render_with`],
+/// except that a template with no placeholders (or escapes) is returned as-is, even if it is owned.
+///
+/// # Errors
+///
+/// Returns an error if the template is malformed, or `lookup` returns `None` for a placeholder.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// use mstr::template;
+///
+/// let lookup = |name: &str| (name == "n").then_some("3");
+/// assert_eq!(template::render(MStr::new_borrowed("${n} items"), lookup).unwrap(), "3 items");
+///
+/// let owned = MStr::new_owned("costs 5$");
+/// let ptr = owned.as_ptr();
+/// assert_eq!(template::render(owned, lookup).unwrap().as_ptr(), ptr);
+/// ```
+pub fn render<V: AsRef<str>>(
+    template: MStr<'_>,
+    lookup: impl FnMut(&str) -> Option<V>,
+) -> Result<MStr<'_>, TemplateError> {
+    if !template.contains('$') {
+        return Ok(template);
+    }
+
+    let t = Template::parse(template)?;
+    if t.is_source() {
+        return Ok(t.source);
+    }
+    t.render_with(lookup)
+}
+
+fn parse_parts(s: &str) -> Result<Vec<Part>, TemplateError> {
+    let bytes = s.as_bytes();
+    let mut parts = Vec::new();
+    let mut literal_start = 0;
+    let mut i = 0;
+
+    while let Some(pos) = s[i..].find('$') {
+        let dollar = i + pos;
+        match bytes.get(dollar + 1) {
+            // `$$`: keep the first `$`, and start the next literal after the second
+            Some(b'$') => {
+                push_literal(&mut parts, literal_start..dollar + 1);
+                literal_start = dollar + 2;
+                i = dollar + 2;
+            }
+            Some(b'{') => {
+                let name_start = dollar + 2;
+                let Some(len) = s[name_start..].find('}') else {
+                    return Err(TemplateError {
+                        start: dollar,
+                        end: s.len(),
+                        kind: ErrorKind::Unclosed,
+                    });
+                };
+                let name = name_start..name_start + len;
+                let span = dollar..name.end + 1;
+
+                if name.is_empty() {
+                    return Err(TemplateError {
+                        start: span.start,
+                        end: span.end,
+                        kind: ErrorKind::EmptyName,
+                    });
+                }
+                if !s[name.clone()].bytes().all(is_name_byte) {
+                    return Err(TemplateError {
+                        start: span.start,
+                        end: span.end,
+                        kind: ErrorKind::InvalidName,
+                    });
+                }
+
+                push_literal(&mut parts, literal_start..dollar);
+                literal_start = span.end;
+                i = span.end;
+                parts.push(Part::Placeholder { name, span });
+            }
+            _ => i = dollar + 1,
+        }
+    }
+
+    push_literal(&mut parts, literal_start..s.len());
+    Ok(parts)
+}
+
+#[inline]
+fn push_literal(parts: &mut Vec<Part>, range: Range<usize>) {
+    if !range.is_empty() {
+        parts.push(Part::Literal(range));
+    }
+}
+
+#[inline]
+fn is_name_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'_' | b'.' | b'-')
+}
+
+// ===== Vars =====
+
+/// A set of named values to render a [`Template`] with.
+///
+/// Implemented for maps from strings to strings.
+/// For anything else, use [`Template::render_with`].
+pub trait Vars {
+    /// Returns the value of the placeholder called `name`.
+    fn get(&self, name: &str) -> Option<&str>;
+}
+
+impl<T: Vars + ?Sized> Vars for &T {
+    #[inline]
+    fn get(&self, name: &str) -> Option<&str> {
+        T::get(self, name)
+    }
+}
+
+impl<K: Borrow<str> + Ord, V: AsRef<str>> Vars for BTreeMap<K, V> {
+    #[inline]
+    fn get(&self, name: &str) -> Option<&str> {
+        BTreeMap::get(self, name).map(V::as_ref)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> Vars for std::collections::HashMap<K, V, S>
+where
+    K: Borrow<str> + core::hash::Hash + Eq,
+    V: AsRef<str>,
+    S: core::hash::BuildHasher,
+{
+    #[inline]
+    fn get(&self, name: &str) -> Option<&str> {
+        std::collections::HashMap::get(self, name).map(V::as_ref)
+    }
+}
+
+// ===== Error =====
+
+/// An error from parsing or rendering a [`Template`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateError {
+    start: usize,
+    end: usize,
+    kind: ErrorKind,
+}
+
+/// The kind of a [`TemplateError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A `${` with no closing `}`.
+    Unclosed,
+    /// A `${}` placeholder.
+    EmptyName,
+    /// A placeholder name with a character other than an ASCII letter, digit, `_`, `.` or `-`.
+    InvalidName,
+    /// A placeholder with no value, when rendering.
+    UnknownPlaceholder,
+}
+
+impl TemplateError {
+    /// Returns the byte span of the placeholder in the template, starting at its `$`.
+    ///
+    /// For [`ErrorKind::Unclosed`], this runs to the end of the template.
+    #[inline]
+    #[must_use]
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns what was wrong with the placeholder.
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let msg = match self.kind {
+            ErrorKind::Unclosed => "unclosed placeholder",
+            ErrorKind::EmptyName => "empty placeholder",
+            ErrorKind::InvalidName => "invalid placeholder name",
+            ErrorKind::UnknownPlaceholder => "unknown placeholder",
+        };
+        write!(f, "{msg} at {}..{}", self.start, self.end)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TemplateError {}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn parse(s: &str) -> Result<Template<'_>, TemplateError> {
+        Template::parse(MStr::new_borrowed(s))
+    }
+
+    fn err(span: Range<usize>, kind: ErrorKind) -> TemplateError {
+        TemplateError {
+            start: span.start,
+            end: span.end,
+            kind,
+        }
+    }
+
+    fn vars() -> BTreeMap<&'static str, &'static str> {
+        BTreeMap::from([("a", "1"), ("b.c", "two"), ("x_y-z", "")])
+    }
+
+    #[test]
+    fn unchanged() {
+        for s in ["", "plain text", "5$ or $5", "trailing $", "{braces}"] {
+            let t = parse(s).unwrap();
+            assert!(!t.has_placeholders());
+
+            let r = t.render(&vars()).unwrap();
+            assert!(r.is_borrowed());
+            assert_eq!(r.as_ptr(), s.as_ptr());
+
+            let r = render(MStr::new_borrowed(s), |_| None::<&str>).unwrap();
+            assert_eq!(r.as_ptr(), s.as_ptr());
+        }
+
+        let owned = MStr::new_owned("it's 5$");
+        let ptr = owned.as_ptr();
+        let r = render(owned, |_| None::<&str>).unwrap();
+        assert!(r.is_owned());
+        assert_eq!(r.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn render_vars() {
+        for (s, expected) in [
+            ("${a}", "1"),
+            ("${a}${a}", "11"),
+            ("<${b.c}>", "<two>"),
+            ("[${x_y-z}]", "[]"),
+            ("$$", "$"),
+            ("$$$$", "$$"),
+            ("$${a}", "${a}"),
+            ("$$${a}", "$1"),
+            ("$ ${a} $", "$ 1 $"),
+            ("${a}}", "1}"),
+            ("日本${a}語", "日本1語"),
+        ] {
+            let t = parse(s).unwrap();
+            assert_eq!(t.render(&vars()).unwrap(), expected, "{s:?}");
+            assert_eq!(
+                render(MStr::new_borrowed(s), |name| vars().get(name).copied()).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        for (s, e) in [
+            ("${", err(0..2, ErrorKind::Unclosed)),
+            ("ab ${a", err(3..6, ErrorKind::Unclosed)),
+            ("${a} ${", err(5..7, ErrorKind::Unclosed)),
+            ("${}", err(0..3, ErrorKind::EmptyName)),
+            ("x${ a }", err(1..7, ErrorKind::InvalidName)),
+            ("${a{b}", err(0..6, ErrorKind::InvalidName)),
+            ("${名前}", err(0..9, ErrorKind::InvalidName)),
+        ] {
+            assert_eq!(parse(s).unwrap_err(), e, "{s:?}");
+            assert_eq!(render(MStr::new_borrowed(s), |_| Some("")).unwrap_err(), e);
+        }
+    }
+
+    #[test]
+    fn unknown_placeholder() {
+        let t = parse("${a} ${missing} ${also_missing}").unwrap();
+        let e = t.render(&vars()).unwrap_err();
+        assert_eq!(e, err(5..15, ErrorKind::UnknownPlaceholder));
+        assert_eq!(e.to_string(), "unknown placeholder at 5..15");
+    }
+
+    #[test]
+    fn segments() {
+        let t = parse("a$$b${x}${y}c$").unwrap();
+        assert!(t.has_placeholders());
+        assert!(t.placeholders().eq(["x", "y"]));
+        assert_eq!(
+            t.segments().collect::<Vec<_>>(),
+            [
+                Segment::Literal("a$"),
+                Segment::Literal("b"),
+                Segment::Placeholder {
+                    name: "x",
+                    span: 4..8
+                },
+                Segment::Placeholder {
+                    name: "y",
+                    span: 8..12
+                },
+                Segment::Literal("c$"),
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_map() {
+        let vars: std::collections::HashMap<String, String> =
+            [("a".to_string(), "1".to_string())].into_iter().collect();
+        assert_eq!(parse("${a}").unwrap().render(&vars).unwrap(), "1");
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Template<'_>>();
+        assert_send_sync::<TemplateError>();
+    }
+}
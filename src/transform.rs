@@ -0,0 +1,382 @@
+use crate::MStr;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use core::fmt::{self, Debug, Formatter};
+
+// ===== Transform =====
+
+/// A chain of "maybe change" steps on an `MStr`, that copies a borrowed string at most once.
+///
+/// Created by [`MStr::transform`].
+/// Each step runs immediately, and [`finish`](Transform::finish) returns the result.
+///
+/// - [`slice`](Transform::slice) narrows the string (like [`str::trim`]).
+///   Borrowed strings stay borrowed, and owned strings are cut down in place.
+/// - [`map`](Transform::map) replaces the string, if the step returns `Some`.
+/// - [`edit`](Transform::edit) modifies the string in place, if the step says it needs to.
+///   A borrowed string is copied the first time this happens, and every later edit reuses that buffer.
+///
+/// Steps that don't change anything never allocate,
+/// so if nothing changes, `finish` returns the original string (still borrowed, if it was).
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::MStr;
+/// fn clean(s: MStr<'_>) -> MStr<'_> {
+///     s.transform()
+///         .slice(str::trim)
+///         .map(|s| s.contains("\r\n").then(|| s.replace("\r\n", "\n")))
+///         .edit(
+///             |s| s.bytes().any(|b| b.is_ascii_uppercase()),
+///             |s| s.make_ascii_lowercase(),
+///         )
+///         .finish()
+/// }
+///
+/// let s = clean(MStr::new_borrowed("  already clean\n"));
+/// assert_eq!(s, "already clean");
+/// assert!(s.is_borrowed());
+///
+/// let s = clean(MStr::new_borrowed("  Two\r\nLines "));
+/// assert_eq!(s, "two\nlines");
+/// assert!(s.is_owned());
+/// ```
+pub struct Transform<'a> {
+    state: State<'a>,
+    changed: bool,
+}
+
+enum State<'a> {
+    Borrowed(&'a str),
+    // kept as a `String` (not a `Box<str>`) so edits can reuse its capacity
+    Owned(String),
+}
+
+impl<'a> MStr<'a> {
+    /// Starts a [`Transform`] chain on this string.
+    ///
+    /// Owned strings keep their buffer, so in-place steps don't allocate at all.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("  padded  ").transform().slice(str::trim).finish();
+    /// assert_eq!(s, "padded");
+    /// assert!(s.is_borrowed());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn transform(self) -> Transform<'a> {
+        let state = match self.as_borrowed() {
+            Some(s) => State::Borrowed(s),
+            None => State::Owned(self.into_string()),
+        };
+
+        Transform {
+            state,
+            changed: false,
+        }
+    }
+}
+
+impl<'a> Transform<'a> {
+    /// Narrows the string to the slice `f` returns.
+    ///
+    /// Borrowed strings stay borrowed, and owned strings are cut down in place.
+    /// If `f` returns a string that isn't part of its input (like a `&'static str`), it is copied.
+    ///
+    /// The step counts as a change unless `f` returns its whole input.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_owned("key = value").transform()
+    ///     .slice(|s| s.split_once('=').map_or(s, |(_, value)| value))
+    ///     .slice(str::trim_start)
+    ///     .finish();
+    /// assert_eq!(s, "value");
+    /// ```
+    #[must_use]
+    pub fn slice(mut self, f: impl for<'s> FnOnce(&'s str) -> &'s str) -> Transform<'a> {
+        if let State::Borrowed(s) = self.state {
+            let out = f(s);
+            self.changed |= !same_slice(s, out);
+            self.state = State::Borrowed(out);
+            return self;
+        }
+
+        if let State::Owned(buf) = &mut self.state {
+            let out = f(buf);
+            if same_slice(buf, out) {
+                return self;
+            }
+            self.changed = true;
+
+            match subslice_range(buf, out) {
+                Some((start, end)) => {
+                    buf.truncate(end);
+                    buf.drain(..start);
+                }
+                None => *buf = out.to_owned(),
+            }
+        }
+        self
+    }
+
+    /// Replaces the string with what `f` returns, if it returns `Some`.
+    ///
+    /// `f` returns `None` if nothing needs to change (which is counted as no change).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("a-b-c").transform()
+    ///     .map(|s| s.contains('-').then(|| s.replace('-', "_")))
+    ///     .finish();
+    /// assert_eq!(s, "a_b_c");
+    /// ```
+    #[must_use]
+    pub fn map(mut self, f: impl FnOnce(&str) -> Option<String>) -> Transform<'a> {
+        if let Some(out) = f(self.as_str()) {
+            self.state = State::Owned(out);
+            self.changed = true;
+        }
+        self
+    }
+
+    /// Edits the string in place with `edit`, if `needs_edit` returns `true`.
+    ///
+    /// If the string is still borrowed, it is copied first.
+    /// Every later edit reuses the same buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("shout").transform()
+    ///     .edit(|s| !s.ends_with('!'), |s| s.push('!'))
+    ///     .edit(|s| s.bytes().any(|b| b.is_ascii_lowercase()), |s| s.make_ascii_uppercase())
+    ///     .finish();
+    /// assert_eq!(s, "SHOUT!");
+    /// ```
+    #[must_use]
+    pub fn edit(
+        mut self,
+        needs_edit: impl FnOnce(&str) -> bool,
+        edit: impl FnOnce(&mut String),
+    ) -> Transform<'a> {
+        if !needs_edit(self.as_str()) {
+            return self;
+        }
+
+        if let State::Borrowed(s) = self.state {
+            self.state = State::Owned(s.to_owned());
+        }
+        if let State::Owned(buf) = &mut self.state {
+            edit(buf);
+        }
+        self.changed = true;
+        self
+    }
+
+    /// Returns the string as it is so far.
+    #[inline]
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match &self.state {
+            State::Borrowed(s) => s,
+            State::Owned(s) => s,
+        }
+    }
+
+    /// Returns `true` if any step so far changed the string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let t = MStr::new_borrowed("trimmed").transform().slice(str::trim);
+    /// assert!(!t.changed());
+    ///
+    /// let t = t.map(|s| Some(s.repeat(2)));
+    /// assert!(t.changed());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn changed(&self) -> bool {
+        self.changed
+    }
+
+    /// Finishes the chain, and returns the resulting string.
+    ///
+    /// If nothing changed, this is the original string.
+    #[inline]
+    #[must_use]
+    pub fn finish(self) -> MStr<'a> {
+        match self.state {
+            State::Borrowed(s) => MStr::new_borrowed(s),
+            State::Owned(s) => MStr::new_owned(s),
+        }
+    }
+}
+
+impl Debug for Transform<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transform")
+            .field("str", &self.as_str())
+            .field("owned", &matches!(self.state, State::Owned(_)))
+            .field("changed", &self.changed)
+            .finish()
+    }
+}
+
+// whether `a` and `b` are the exact same slice
+#[inline]
+fn same_slice(a: &str, b: &str) -> bool {
+    a.as_ptr() == b.as_ptr() && a.len() == b.len()
+}
+
+// the byte range of `sub` in `s`, if it is a slice of `s`
+fn subslice_range(s: &str, sub: &str) -> Option<(usize, usize)> {
+    let start = (sub.as_ptr() as usize).checked_sub(s.as_ptr() as usize)?;
+    let end = start + sub.len();
+    (end <= s.len()).then_some((start, end))
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upper_ascii(t: Transform<'_>) -> Transform<'_> {
+        t.edit(
+            |s| s.bytes().any(|b| b.is_ascii_lowercase()),
+            |s| s.make_ascii_uppercase(),
+        )
+    }
+
+    #[test]
+    fn unchanged_borrowed() {
+        let input = "UNCHANGED";
+        let t = upper_ascii(
+            MStr::new_borrowed(input)
+                .transform()
+                .slice(str::trim)
+                .map(|_| None),
+        );
+        assert!(!t.changed());
+
+        let s = t.finish();
+        assert!(s.is_borrowed());
+        assert_eq!(s.as_ptr(), input.as_ptr());
+    }
+
+    #[test]
+    fn unchanged_owned() {
+        let owned = MStr::new_owned("UNCHANGED");
+        let ptr = owned.as_ptr();
+
+        let t = upper_ascii(owned.transform().slice(str::trim).map(|_| None));
+        assert!(!t.changed());
+
+        let s = t.finish();
+        assert!(s.is_owned());
+        assert_eq!(s.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn slice_borrowed_stays_borrowed() {
+        let input = "  [value]  ";
+        let s = MStr::new_borrowed(input)
+            .transform()
+            .slice(str::trim)
+            .slice(|s| s.trim_start_matches('[').trim_end_matches(']'))
+            .finish();
+
+        assert_eq!(s, "value");
+        assert!(s.is_borrowed());
+        assert_eq!(s.as_ptr(), input[3..].as_ptr());
+    }
+
+    #[test]
+    fn slice_owned_in_place() {
+        let t = MStr::new_owned("  [value]  ")
+            .transform()
+            .slice(str::trim)
+            .slice(|s| &s[1..s.len() - 1]);
+        assert!(t.changed());
+        assert_eq!(t.as_str(), "value");
+
+        let s = t.finish();
+        assert!(s.is_owned());
+        assert_eq!(s, "value");
+    }
+
+    #[test]
+    fn slice_static() {
+        let t = MStr::new_owned("owned")
+            .transform()
+            .slice(|s| if s.is_empty() { s } else { "static" });
+        assert!(t.changed());
+        assert_eq!(t.finish(), "static");
+
+        let s = MStr::new_borrowed("borrowed")
+            .transform()
+            .slice(|_| "static")
+            .finish();
+        assert_eq!(s, "static");
+        assert!(s.is_borrowed());
+    }
+
+    #[test]
+    fn copies_once() {
+        // the first edit copies, and the rest reuse that buffer
+        let t = MStr::new_borrowed("abc")
+            .transform()
+            .edit(|_| true, |s| s.reserve(16))
+            .edit(|_| true, |s| s.push('d'));
+        let State::Owned(buf) = &t.state else {
+            panic!("not owned");
+        };
+        let ptr = buf.as_ptr();
+
+        let t = upper_ascii(t.edit(|_| true, |s| s.push('e')).slice(|s| &s[1..]));
+        let State::Owned(buf) = &t.state else {
+            panic!("not owned");
+        };
+        assert_eq!(buf.as_ptr(), ptr);
+        assert_eq!(t.finish(), "BCDE");
+    }
+
+    #[test]
+    fn mixed_steps() {
+        let s = MStr::new_borrowed(" a-b ")
+            .transform()
+            .slice(str::trim)
+            .map(|s| s.contains('-').then(|| s.replace('-', "--")))
+            .slice(|s| s.strip_prefix('a').unwrap_or(s));
+        assert!(s.changed());
+        assert_eq!(s.finish(), "--b");
+    }
+
+    #[test]
+    fn debug() {
+        let t = MStr::new_borrowed("x").transform();
+        assert_eq!(
+            alloc::format!("{t:?}"),
+            r#"Transform { str: "x", owned: false, changed: false }"#
+        );
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Transform<'_>>();
+    }
+}
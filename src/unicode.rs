@@ -0,0 +1,317 @@
+//! Unicode normalization and case conversion that borrow when nothing changes.
+//!
+//! Normalization uses [unicode-normalization](https://docs.rs/unicode-normalization)'s quick-check,
+//! so strings that are already normalized (which is almost all of them) are returned as-is, without allocating.
+//!
+//! Requires the `unicode` feature.
+
+use crate::case_insensitive::Unicode;
+use crate::MStr;
+use alloc::string::String;
+use unicode_normalization::{is_nfc, is_nfd, is_nfkc, is_nfkd, UnicodeNormalization};
+
+// ===== Normalization =====
+
+impl<'a> MStr<'a> {
+    /// Normalizes this string to Normalization Form C (canonical composition).
+    ///
+    /// If the string is already in NFC, `self` is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("cafe\u{301}").nfc();
+    /// assert_eq!(s, "caf\u{e9}");
+    ///
+    /// let s = MStr::new_borrowed("caf\u{e9}").nfc();
+    /// assert!(s.is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn nfc(self) -> MStr<'a> {
+        if is_nfc(&self) {
+            return self;
+        }
+        MStr::new_owned(self.chars().nfc().collect::<String>())
+    }
+
+    /// Normalizes this string to Normalization Form D (canonical decomposition).
+    ///
+    /// If the string is already in NFD, `self` is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("caf\u{e9}").nfd();
+    /// assert_eq!(s, "cafe\u{301}");
+    /// ```
+    #[must_use]
+    pub fn nfd(self) -> MStr<'a> {
+        if is_nfd(&self) {
+            return self;
+        }
+        MStr::new_owned(self.chars().nfd().collect::<String>())
+    }
+
+    /// Normalizes this string to Normalization Form KC (compatibility composition).
+    ///
+    /// If the string is already in NFKC, `self` is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("\u{FB01}le \u{2460}").nfkc();
+    /// assert_eq!(s, "file 1");
+    /// ```
+    #[must_use]
+    pub fn nfkc(self) -> MStr<'a> {
+        if is_nfkc(&self) {
+            return self;
+        }
+        MStr::new_owned(self.chars().nfkc().collect::<String>())
+    }
+
+    /// Normalizes this string to Normalization Form KD (compatibility decomposition).
+    ///
+    /// If the string is already in NFKD, `self` is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("\u{FB01}anc\u{e9}").nfkd();
+    /// assert_eq!(s, "fiance\u{301}");
+    /// ```
+    #[must_use]
+    pub fn nfkd(self) -> MStr<'a> {
+        if is_nfkd(&self) {
+            return self;
+        }
+        MStr::new_owned(self.chars().nfkd().collect::<String>())
+    }
+}
+
+// ===== Case =====
+
+impl<'a> MStr<'a> {
+    /// Case-folds this string, for case-insensitive comparisons.
+    ///
+    /// Every character is converted to uppercase, and then to lowercase,
+    /// so two strings fold to the same string exactly when they are equal as [`UniCaseMStr`](crate::UniCaseMStr)s
+    /// (`"Straße"` and `"STRASSE"` both fold to `"strasse"`).
+    /// Like there, `"ẞ"` (capital sharp s) folds to `"ss"` rather than to its lowercase `"ß"`.
+    ///
+    /// If folding doesn't change anything, `self` is returned unchanged.
+    ///
+    /// No normalization is done, so use [`nfc`](MStr::nfc) (or [`nfkc`](MStr::nfkc)) first
+    /// if composed and decomposed characters should compare equal.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// assert_eq!(MStr::new_borrowed("Straße").case_fold(), "strasse");
+    /// assert!(MStr::new_borrowed("already folded").case_fold().is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn case_fold(self) -> MStr<'a> {
+        self.map_chars(|c, out| Unicode::fold_char(c).for_each(|c| out.push(c)))
+    }
+
+    /// Converts this string to lowercase, like [`str::to_lowercase`].
+    ///
+    /// If the string has no uppercase characters, `self` is returned unchanged.
+    /// Owned ASCII strings are converted in place.
+    ///
+    /// This is named differently from `str::to_lowercase` (which `MStr` derefs to),
+    /// so that enabling this feature doesn't change what `mstr.to_lowercase()` returns.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// assert_eq!(MStr::new_borrowed("ÖL & Wasser").into_lowercase(), "öl & wasser");
+    /// assert!(MStr::new_borrowed("öl").into_lowercase().is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn into_lowercase(self) -> MStr<'a> {
+        if self.is_ascii() {
+            if !self.bytes().any(|b| b.is_ascii_uppercase()) {
+                return self;
+            }
+            let mut s = self.into_boxed();
+            s.make_ascii_lowercase();
+            return MStr::new_owned(s);
+        }
+
+        // `str::to_lowercase` handles the final sigma, which per-char conversion doesn't
+        if self.chars().all(|c| is_unchanged(c.to_lowercase(), c)) {
+            return self;
+        }
+        MStr::new_owned(self.to_lowercase())
+    }
+
+    /// Converts this string to uppercase, like [`str::to_uppercase`].
+    ///
+    /// If the string has no lowercase characters, `self` is returned unchanged.
+    /// Owned ASCII strings are converted in place.
+    ///
+    /// This is named differently from `str::to_uppercase` (which `MStr` derefs to),
+    /// so that enabling this feature doesn't change what `mstr.to_uppercase()` returns.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// assert_eq!(MStr::new_borrowed("straße").into_uppercase(), "STRASSE");
+    /// assert!(MStr::new_borrowed("ÖL").into_uppercase().is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn into_uppercase(self) -> MStr<'a> {
+        if self.is_ascii() {
+            if !self.bytes().any(|b| b.is_ascii_lowercase()) {
+                return self;
+            }
+            let mut s = self.into_boxed();
+            s.make_ascii_uppercase();
+            return MStr::new_owned(s);
+        }
+
+        if self.chars().all(|c| is_unchanged(c.to_uppercase(), c)) {
+            return self;
+        }
+        MStr::new_owned(self.to_uppercase())
+    }
+
+    // converts every char with `f`, unless every char converts to itself
+    fn map_chars(self, mut f: impl FnMut(char, &mut String)) -> MStr<'a> {
+        let mut buf = String::new();
+        let first = self.char_indices().find(|&(_, c)| {
+            buf.clear();
+            f(c, &mut buf);
+            buf.chars().ne([c])
+        });
+        let Some((first, _)) = first else {
+            return self;
+        };
+
+        let mut out = String::with_capacity(self.len() + 8);
+        out.push_str(&self[..first]);
+        for c in self[first..].chars() {
+            f(c, &mut out);
+        }
+        MStr::new_owned(out)
+    }
+}
+
+// whether a case mapping of `c` is just `c`
+#[inline]
+fn is_unchanged(mut mapped: impl Iterator<Item = char>, c: char) -> bool {
+    mapped.next() == Some(c) && mapped.next().is_none()
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UniCaseMStr;
+
+    #[test]
+    fn normalization_borrows() {
+        for s in ["", "ascii", "caf\u{e9}", "日本語", "🐸"] {
+            assert!(MStr::new_borrowed(s).nfc().is_borrowed());
+            assert!(MStr::new_borrowed(s).nfkc().is_borrowed());
+        }
+        for s in ["", "ascii", "cafe\u{301}"] {
+            assert!(MStr::new_borrowed(s).nfd().is_borrowed());
+            assert!(MStr::new_borrowed(s).nfkd().is_borrowed());
+        }
+
+        let owned = MStr::new_owned("caf\u{e9}");
+        let ptr = owned.as_ptr();
+        assert_eq!(owned.nfc().as_ptr(), ptr);
+    }
+
+    #[test]
+    fn normalization() {
+        let composed = "\u{c5}ngstr\u{f6}m";
+        let decomposed = "A\u{30a}ngstro\u{308}m";
+
+        assert_eq!(MStr::new_borrowed(decomposed).nfc(), composed);
+        assert_eq!(MStr::new_borrowed(composed).nfd(), decomposed);
+
+        // canonical reordering of combining marks
+        assert_eq!(
+            MStr::new_borrowed("a\u{301}\u{316}").nfd(),
+            "a\u{316}\u{301}"
+        );
+        // the angstrom sign is a singleton decomposition
+        assert_eq!(MStr::new_borrowed("\u{212b}").nfc(), "\u{c5}");
+
+        // compatibility forms only change with nfk*
+        let s = "\u{FB01} \u{2460} \u{FF21}";
+        assert!(MStr::new_borrowed(s).nfc().is_borrowed());
+        assert_eq!(MStr::new_borrowed(s).nfkc(), "fi 1 A");
+        assert_eq!(
+            MStr::new_borrowed("\u{1E9B}\u{323}").nfkd(),
+            "s\u{323}\u{307}"
+        );
+    }
+
+    #[test]
+    fn case_fold() {
+        assert_eq!(MStr::new_borrowed("Hello").case_fold(), "hello");
+        assert_eq!(MStr::new_borrowed("STRASSE").case_fold(), "strasse");
+        assert_eq!(MStr::new_borrowed("ΣΊΣΥΦΟΣ").case_fold(), "σίσυφοσ");
+        assert_eq!(MStr::new_borrowed("σίσυφος").case_fold(), "σίσυφοσ");
+        assert_eq!(MStr::new_borrowed("ẞ").case_fold(), "ss");
+        assert_eq!(MStr::new_borrowed("ß").case_fold(), "ss");
+        assert!(MStr::new_borrowed("folded 🐸").case_fold().is_borrowed());
+
+        // agrees with UniCaseMStr
+        for (a, b) in [
+            ("Straße", "STRASSE"),
+            ("ǅ", "ǆ"),
+            ("ﬁ", "FI"),
+            ("Ω", "ω"),
+            ("STRAẞE", "straße"),
+        ] {
+            let (a, b) = (MStr::new_borrowed(a), MStr::new_borrowed(b));
+            assert_eq!(UniCaseMStr::new(a.clone()), UniCaseMStr::new(b.clone()));
+            assert_eq!(a.case_fold(), b.case_fold());
+        }
+    }
+
+    #[test]
+    fn lower_upper() {
+        assert_eq!(MStr::new_borrowed("ABC def").into_lowercase(), "abc def");
+        assert_eq!(MStr::new_borrowed("ABC def").into_uppercase(), "ABC DEF");
+        assert_eq!(MStr::new_borrowed("ὈΔΥΣΣΕΎΣ").into_lowercase(), "ὀδυσσεύς");
+        assert_eq!(MStr::new_borrowed("ﬁ").into_uppercase(), "FI");
+
+        assert!(MStr::new_borrowed("abc 123 日本")
+            .into_lowercase()
+            .is_borrowed());
+        assert!(MStr::new_borrowed("ABC 123 日本")
+            .into_uppercase()
+            .is_borrowed());
+        // final sigma is only lowercase at the end of a word
+        assert!(MStr::new_borrowed("σς").into_lowercase().is_borrowed());
+    }
+
+    #[test]
+    fn lower_upper_in_place() {
+        let owned = MStr::new_owned("MiXeD");
+        let ptr = owned.as_ptr();
+        let lower = owned.into_lowercase();
+        assert_eq!(lower, "mixed");
+        assert_eq!(lower.as_ptr(), ptr);
+
+        let upper = lower.into_uppercase();
+        assert_eq!(upper, "MIXED");
+        assert_eq!(upper.as_ptr(), ptr);
+    }
+}
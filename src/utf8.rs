@@ -0,0 +1,346 @@
+use crate::MStr;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter};
+use core::str::Utf8Error;
+use core::{fmt, str};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Byte buffers that [`MStr::from_utf8`] and friends accept.
+///
+/// Implemented for `&'a [u8]` (which produces a borrowed `MStr`),
+/// `Vec<u8>` and `Box<[u8]>` (which produce an owned `MStr`, reusing the buffer),
+/// and `Cow<'a, [u8]>` (which produces either).
+///
+/// This trait is sealed, and can't be implemented outside this crate.
+pub trait Utf8Input<'a>: private::Sealed + Sized {
+    #[doc(hidden)]
+    fn as_bytes(&self) -> &[u8];
+
+    // SAFETY: the bytes must be valid UTF-8
+    #[doc(hidden)]
+    unsafe fn into_mstr_unchecked(self) -> MStr<'a>;
+}
+
+impl private::Sealed for &[u8] {}
+impl private::Sealed for Vec<u8> {}
+impl private::Sealed for Box<[u8]> {}
+impl private::Sealed for Cow<'_, [u8]> {}
+
+impl<'a> Utf8Input<'a> for &'a [u8] {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    #[inline]
+    unsafe fn into_mstr_unchecked(self) -> MStr<'a> {
+        MStr::new_borrowed(unsafe { str::from_utf8_unchecked(self) })
+    }
+}
+
+impl<'a> Utf8Input<'a> for Vec<u8> {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    #[inline]
+    unsafe fn into_mstr_unchecked(self) -> MStr<'a> {
+        MStr::new_owned(unsafe { String::from_utf8_unchecked(self) })
+    }
+}
+
+impl<'a> Utf8Input<'a> for Box<[u8]> {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    #[inline]
+    unsafe fn into_mstr_unchecked(self) -> MStr<'a> {
+        MStr::new_owned(unsafe { alloc::str::from_boxed_utf8_unchecked(self) })
+    }
+}
+
+impl<'a> Utf8Input<'a> for Cow<'a, [u8]> {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    #[inline]
+    unsafe fn into_mstr_unchecked(self) -> MStr<'a> {
+        match self {
+            Cow::Borrowed(b) => unsafe { b.into_mstr_unchecked() },
+            Cow::Owned(v) => unsafe { v.into_mstr_unchecked() },
+        }
+    }
+}
+
+// ===== MStr Constructors =====
+
+impl<'a> MStr<'a> {
+    /// Converts a byte buffer to an `MStr`, checking that it is valid UTF-8.
+    ///
+    /// Borrowed input (`&'a [u8]`) produces a borrowed `MStr`,
+    /// and owned input (`Vec<u8>`, `Box<[u8]>`) produces an owned `MStr` that reuses the buffer
+    /// (a `Vec` [may reallocate](String::into_boxed_str) if it has excess capacity).
+    /// See [`Utf8Input`] for the accepted types.
+    ///
+    /// # Errors
+    ///
+    /// If the bytes aren't valid UTF-8, the original buffer is returned inside the error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let bytes = b"frog".as_slice();
+    /// let mstr = MStr::from_utf8(bytes).unwrap();
+    /// assert!(mstr.is_borrowed());
+    /// assert_eq!(mstr, "frog");
+    ///
+    /// let mstr = MStr::from_utf8(b"toad".to_vec()).unwrap();
+    /// assert!(mstr.is_owned());
+    ///
+    /// let err = MStr::from_utf8(vec![b'a', 0xff]).unwrap_err();
+    /// assert_eq!(err.valid_up_to(), 1);
+    /// assert_eq!(err.into_bytes(), [b'a', 0xff]);
+    /// ```
+    pub fn from_utf8<B: Utf8Input<'a>>(bytes: B) -> Result<MStr<'a>, FromUtf8Error<B>> {
+        match str::from_utf8(bytes.as_bytes()) {
+            // SAFETY: just checked
+            Ok(_) => Ok(unsafe { bytes.into_mstr_unchecked() }),
+            Err(error) => Err(FromUtf8Error { bytes, error }),
+        }
+    }
+
+    /// Converts a byte buffer to an `MStr`, replacing invalid UTF-8 sequences with `U+FFFD`.
+    ///
+    /// If the bytes are valid UTF-8, this behaves exactly like [`MStr::from_utf8`]
+    /// (borrowed input stays borrowed, and owned buffers are reused).
+    /// Otherwise, a new owned string is allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let valid = MStr::from_utf8_lossy(b"frog".as_slice());
+    /// assert!(valid.is_borrowed());
+    ///
+    /// let invalid = MStr::from_utf8_lossy(b"fr\xffg".as_slice());
+    /// assert!(invalid.is_owned());
+    /// assert_eq!(invalid, "fr\u{FFFD}g");
+    /// ```
+    #[must_use]
+    pub fn from_utf8_lossy<B: Utf8Input<'a>>(bytes: B) -> MStr<'a> {
+        match MStr::from_utf8(bytes) {
+            Ok(s) => s,
+            Err(e) => MStr::new_owned(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+        }
+    }
+
+    /// Converts a byte buffer to an `MStr`, without checking that it is valid UTF-8.
+    ///
+    /// See [`MStr::from_utf8`] for how the buffer is used.
+    ///
+    /// # Safety
+    ///
+    /// The bytes must be valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// // SAFETY: a string literal is always valid UTF-8
+    /// let mstr = unsafe { MStr::from_utf8_unchecked("frog".as_bytes()) };
+    /// assert_eq!(mstr, "frog");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub unsafe fn from_utf8_unchecked<B: Utf8Input<'a>>(bytes: B) -> MStr<'a> {
+        // SAFETY: guaranteed by the caller
+        unsafe { bytes.into_mstr_unchecked() }
+    }
+}
+
+// ===== Error =====
+
+/// The error returned by [`MStr::from_utf8`] when the bytes aren't valid UTF-8.
+///
+/// The original buffer can be recovered with [`into_bytes`](FromUtf8Error::into_bytes).
+#[derive(Clone, PartialEq, Eq)]
+pub struct FromUtf8Error<B> {
+    bytes: B,
+    error: Utf8Error,
+}
+
+impl<B> FromUtf8Error<B> {
+    /// Gets the bytes that failed to convert.
+    #[inline]
+    #[must_use]
+    pub fn as_bytes<'a>(&self) -> &[u8]
+    where
+        B: Utf8Input<'a>,
+    {
+        self.bytes.as_bytes()
+    }
+
+    /// Returns the buffer that failed to convert.
+    #[inline]
+    #[must_use]
+    pub fn into_bytes(self) -> B {
+        self.bytes
+    }
+
+    /// Gets the underlying [`Utf8Error`], with more details about the failure.
+    #[inline]
+    #[must_use]
+    pub const fn utf8_error(&self) -> Utf8Error {
+        self.error
+    }
+
+    /// Returns the length of the valid UTF-8 prefix of the bytes.
+    ///
+    /// See [`Utf8Error::valid_up_to`].
+    #[inline]
+    #[must_use]
+    pub const fn valid_up_to(&self) -> usize {
+        self.error.valid_up_to()
+    }
+}
+
+impl<B> Debug for FromUtf8Error<B> {
+    // the buffer could be huge, so leave it out
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FromUtf8Error")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<B> Display for FromUtf8Error<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<B> std::error::Error for FromUtf8Error<B> {}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    const INVALID: &[u8] = b"ab\xf0\x9f\x90c";
+
+    #[test]
+    fn borrowed() {
+        let bytes = b"frog".as_slice();
+        let s = MStr::from_utf8(bytes).unwrap();
+
+        assert!(s.is_borrowed());
+        assert_eq!(s.as_ptr(), bytes.as_ptr());
+
+        let err = MStr::from_utf8(INVALID).unwrap_err();
+        assert_eq!(err.valid_up_to(), 2);
+        assert_eq!(err.utf8_error().error_len(), Some(3));
+        assert_eq!(err.into_bytes(), INVALID);
+    }
+
+    #[test]
+    fn owned_reuses_buffer() {
+        let v = b"frog".to_vec().into_boxed_slice().into_vec();
+        let ptr = v.as_ptr();
+        let s = MStr::from_utf8(v).unwrap();
+        assert!(s.is_owned());
+        assert_eq!(s.as_ptr(), ptr);
+
+        let b: Box<[u8]> = Box::from(*b"toad");
+        let ptr = b.as_ptr();
+        let s = MStr::from_utf8(b).unwrap();
+        assert!(s.is_owned());
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(s, "toad");
+    }
+
+    #[test]
+    fn owned_error_returns_buffer() {
+        let v = INVALID.to_vec();
+        let ptr = v.as_ptr();
+
+        let err = MStr::from_utf8(v).unwrap_err();
+        assert_eq!(err.as_bytes(), INVALID);
+
+        let v = err.into_bytes();
+        assert_eq!(v.as_ptr(), ptr);
+
+        let err = MStr::from_utf8(Box::<[u8]>::from(INVALID)).unwrap_err();
+        assert_eq!(&*err.into_bytes(), INVALID);
+    }
+
+    #[test]
+    fn cow() {
+        let s = MStr::from_utf8(Cow::Borrowed(b"abc".as_slice())).unwrap();
+        assert!(s.is_borrowed());
+
+        let s = MStr::from_utf8(Cow::<[u8]>::Owned(vec![b'a'])).unwrap();
+        assert!(s.is_owned());
+
+        let err = MStr::from_utf8(Cow::Borrowed(INVALID)).unwrap_err();
+        assert!(matches!(err.into_bytes(), Cow::Borrowed(INVALID)));
+    }
+
+    #[test]
+    fn lossy() {
+        let s = MStr::from_utf8_lossy(b"abc".as_slice());
+        assert!(s.is_borrowed());
+
+        let s = MStr::from_utf8_lossy(b"abc".to_vec());
+        assert!(s.is_owned());
+
+        let s = MStr::from_utf8_lossy(INVALID);
+        assert!(s.is_owned());
+        assert_eq!(s, "ab\u{FFFD}c");
+
+        assert_eq!(
+            MStr::from_utf8_lossy(b"\xff\xfe".to_vec()),
+            "\u{FFFD}\u{FFFD}"
+        );
+    }
+
+    #[test]
+    fn unchecked() {
+        let s = unsafe { MStr::from_utf8_unchecked(vec![b'h', b'i']) };
+        assert!(s.is_owned());
+        assert_eq!(s, "hi");
+    }
+
+    #[test]
+    fn error_fmt() {
+        let err = MStr::from_utf8(INVALID).unwrap_err();
+
+        assert_eq!(
+            alloc::format!("{err}"),
+            "invalid utf-8 sequence of 3 bytes from index 2"
+        );
+        assert!(alloc::format!("{err:?}").starts_with("FromUtf8Error"));
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<FromUtf8Error<Vec<u8>>>();
+        assert_send_sync::<FromUtf8Error<&[u8]>>();
+    }
+}
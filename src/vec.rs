@@ -0,0 +1,903 @@
+use crate::{MStr, MASK, TAG};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ops::Index;
+use core::ptr::NonNull;
+use core::{fmt, slice, str};
+
+/// A compact list of maybe-owned strings.
+///
+/// A `Vec<MStr>` costs 2 words per element, plus one heap allocation for every owned element.
+/// `MStrVec` stores borrowed strings as a pointer into the original data (just like `MStr`),
+/// but packs every owned string back-to-back into a single shared buffer,
+/// so pushing owned strings doesn't allocate once the buffer has grown large enough.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mstr::{MStr, MStrVec};
+/// let mut vec = MStrVec::new();
+/// vec.push("foo");
+/// vec.push(String::from("bar"));
+/// vec.push(MStr::new_owned("baz"));
+///
+/// assert_eq!(vec.len(), 3);
+/// assert_eq!(&vec[1], "bar");
+/// assert_eq!(vec.iter().collect::<Vec<_>>(), ["foo", "bar", "baz"]);
+///
+/// assert!(vec.is_borrowed(0));
+/// assert!(vec.is_owned(1));
+/// ```
+#[derive(Clone, Default)]
+pub struct MStrVec<'a> {
+    spans: Vec<Span>,
+
+    // every owned string, stored back-to-back in push order
+    buf: String,
+
+    _marker: PhantomData<&'a str>,
+}
+
+unsafe impl Send for MStrVec<'_> {}
+unsafe impl Sync for MStrVec<'_> {}
+
+// a single element of an MStrVec
+// uses the same tagging scheme as MStr:
+// if the high bit (TAG) of len is set, start is an offset into the buffer,
+// otherwise start is a pointer to an &'a str
+#[derive(Clone, Copy)]
+struct Span {
+    start: Start,
+    len: usize,
+}
+
+#[derive(Clone, Copy)]
+union Start {
+    ptr: NonNull<u8>,
+    offset: usize,
+}
+
+impl Span {
+    #[inline]
+    const fn is_owned(&self) -> bool {
+        self.len & TAG == TAG
+    }
+
+    #[inline]
+    const fn len(&self) -> usize {
+        self.len & MASK
+    }
+
+    /// # Safety
+    ///
+    /// If this span is owned, `buf` must be the buffer of the `MStrVec` this span belongs to.
+    /// If this span is borrowed, `'s` must not outlive the `'a` of that `MStrVec`.
+    #[inline]
+    unsafe fn as_str<'s>(&self, buf: &'s str) -> &'s str {
+        if self.is_owned() {
+            let start = unsafe { self.start.offset };
+            // SAFETY: owned spans always lie on char boundaries within buf
+            unsafe { buf.get_unchecked(start..start + self.len()) }
+        } else {
+            unsafe { self.as_borrowed() }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// This span must be borrowed, and `'s` must not outlive the `'a` of its `MStrVec`.
+    #[inline]
+    unsafe fn as_borrowed<'s>(&self) -> &'s str {
+        // SAFETY: borrowed spans always come from a valid &'a str
+        unsafe {
+            let bytes = slice::from_raw_parts(self.start.ptr.as_ptr(), self.len());
+            str::from_utf8_unchecked(bytes)
+        }
+    }
+}
+
+impl<'a> MStrVec<'a> {
+    // -- Constructors --
+
+    /// Creates a new, empty `MStrVec`.
+    ///
+    /// This does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStrVec;
+    /// let vec = MStrVec::new();
+    ///
+    /// assert!(vec.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> MStrVec<'a> {
+        MStrVec {
+            spans: Vec::new(),
+            buf: String::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a new, empty `MStrVec` with room for at least `len` strings,
+    /// and at least `bytes` bytes of owned string data.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStrVec;
+    /// let mut vec = MStrVec::with_capacity(2, 6);
+    /// vec.push(String::from("foo"));
+    /// vec.push(String::from("bar"));
+    ///
+    /// assert_eq!(vec.len(), 2);
+    /// ```
+    #[must_use]
+    pub fn with_capacity(len: usize, bytes: usize) -> MStrVec<'a> {
+        MStrVec {
+            spans: Vec::with_capacity(len),
+            buf: String::with_capacity(bytes),
+            _marker: PhantomData,
+        }
+    }
+
+    // -- Mutators --
+
+    /// Appends a string to the end of this `MStrVec`.
+    ///
+    /// If `s` is borrowed, it is stored as a pointer to the original data.
+    /// If `s` is owned, its contents are copied into the shared buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::{MStr, MStrVec};
+    /// let mut vec = MStrVec::new();
+    /// vec.push("foo");
+    /// vec.push(MStr::new_owned("bar"));
+    ///
+    /// assert!(vec.is_borrowed(0));
+    /// assert!(vec.is_owned(1));
+    /// ```
+    pub fn push(&mut self, s: impl Into<MStr<'a>>) {
+        let s = s.into();
+        match s.as_borrowed() {
+            Some(b) => self.push_borrowed(b),
+            None => self.push_owned(&s),
+        }
+    }
+
+    /// Appends a borrowed string to the end of this `MStrVec`.
+    ///
+    /// This never copies the string data.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStrVec;
+    /// let s = "foo";
+    /// let mut vec = MStrVec::new();
+    /// vec.push_borrowed(s);
+    ///
+    /// assert_eq!(vec.get(0).unwrap().as_ptr(), s.as_ptr());
+    /// ```
+    pub fn push_borrowed(&mut self, s: &'a str) {
+        // SAFETY: a str's pointer is never null
+        let ptr = unsafe { NonNull::new_unchecked(s.as_ptr().cast_mut()) };
+
+        self.spans.push(Span {
+            start: Start { ptr },
+            len: s.len(),
+        });
+    }
+
+    /// Appends an owned copy of a string to the end of this `MStrVec`.
+    ///
+    /// The string data is copied into the shared buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStrVec;
+    /// let mut vec = MStrVec::new();
+    /// vec.push_owned("foo");
+    ///
+    /// assert!(vec.is_owned(0));
+    /// assert_eq!(&vec[0], "foo");
+    /// ```
+    pub fn push_owned(&mut self, s: &str) {
+        let offset = self.buf.len();
+        self.buf.push_str(s);
+
+        self.spans.push(Span {
+            start: Start { offset },
+            len: s.len() | TAG,
+        });
+    }
+
+    /// Removes the last string from this `MStrVec` and returns it, or `None` if it is empty.
+    ///
+    /// The returned `MStr` is borrowed if the string was borrowed,
+    /// and owned (copied to a new allocation) if it was owned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStrVec;
+    /// let mut vec = MStrVec::new();
+    /// vec.push("foo");
+    /// vec.push(String::from("bar"));
+    ///
+    /// assert!(vec.pop().unwrap().is_owned());
+    /// assert!(vec.pop().unwrap().is_borrowed());
+    /// assert_eq!(vec.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<MStr<'a>> {
+        let span = self.spans.pop()?;
+
+        if span.is_owned() {
+            // owned data is stored in push order, so the last owned span is at the end of buf
+            let start = unsafe { span.start.offset };
+            let s = MStr::new_owned(&self.buf[start..]);
+            self.buf.truncate(start);
+            Some(s)
+        } else {
+            // SAFETY: span is borrowed, and the returned str lives for 'a
+            Some(MStr::new_borrowed(unsafe { span.as_borrowed() }))
+        }
+    }
+
+    /// Removes every string from this `MStrVec`.
+    ///
+    /// This keeps the allocated capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStrVec;
+    /// let mut vec = MStrVec::from_iter(["a", "b", "c"]);
+    /// vec.clear();
+    ///
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.spans.clear();
+        self.buf.clear();
+    }
+
+    // -- Accessors --
+
+    /// Gets the number of strings in this `MStrVec`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStrVec;
+    /// let vec = MStrVec::from_iter(["a", "b", "c"]);
+    ///
+    /// assert_eq!(vec.len(), 3);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Checks if this `MStrVec` contains no strings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStrVec;
+    /// assert!(MStrVec::new().is_empty());
+    /// assert!(!MStrVec::from_iter([""]).is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Gets the string at `index`, or `None` if it is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStrVec;
+    /// let vec = MStrVec::from_iter(["a", "b"]);
+    ///
+    /// assert_eq!(vec.get(1), Some("b"));
+    /// assert_eq!(vec.get(2), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&str> {
+        let span = self.spans.get(index)?;
+        // SAFETY: the span belongs to self, and &self can't outlive 'a
+        Some(unsafe { span.as_str(&self.buf) })
+    }
+
+    /// Gets the string at `index` as a borrowed `MStr`, or `None` if it is out of bounds.
+    ///
+    /// Borrowed strings point to their original data, and owned strings point into this `MStrVec`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStrVec;
+    /// let vec = MStrVec::from_iter([String::from("a")]);
+    /// let mstr = vec.get_mstr(0).unwrap();
+    ///
+    /// assert!(mstr.is_borrowed());
+    /// assert_eq!(mstr, "a");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn get_mstr(&self, index: usize) -> Option<MStr<'_>> {
+        self.get(index).map(MStr::new_borrowed)
+    }
+
+    /// If the string at `index` is borrowed, get the underlying `&'a str`.
+    ///
+    /// This will return `None` if the string is owned or if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStrVec;
+    /// let mut vec = MStrVec::new();
+    /// vec.push("a");
+    /// vec.push(String::from("b"));
+    ///
+    /// assert_eq!(vec.get_borrowed(0), Some("a"));
+    /// assert_eq!(vec.get_borrowed(1), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn get_borrowed(&self, index: usize) -> Option<&'a str> {
+        let span = self.spans.get(index)?;
+        if span.is_owned() {
+            None
+        } else {
+            // SAFETY: span is borrowed, and the returned str lives for 'a
+            Some(unsafe { span.as_borrowed() })
+        }
+    }
+
+    /// Checks if the string at `index` is owned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn is_owned(&self, index: usize) -> bool {
+        self.spans[index].is_owned()
+    }
+
+    /// Checks if the string at `index` is borrowed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn is_borrowed(&self, index: usize) -> bool {
+        !self.is_owned(index)
+    }
+
+    /// Returns an iterator over the strings in this `MStrVec`, as string slices.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStrVec;
+    /// let vec = MStrVec::from_iter(["a", "b"]);
+    ///
+    /// assert_eq!(vec.iter().collect::<Vec<_>>(), ["a", "b"]);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            spans: self.spans.iter(),
+            buf: &self.buf,
+        }
+    }
+
+    /// Returns an iterator over the strings in this `MStrVec`, as borrowed `MStr`s.
+    ///
+    /// See [`get_mstr`](MStrVec::get_mstr).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::{MStr, MStrVec};
+    /// let vec = MStrVec::from_iter(["a", "b"]);
+    ///
+    /// assert!(vec.iter_mstr().all(|s| s.is_borrowed()));
+    /// ```
+    #[inline]
+    pub fn iter_mstr(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = MStr<'_>> + ExactSizeIterator + FusedIterator {
+        self.iter().map(MStr::new_borrowed)
+    }
+}
+
+// ===== Iterators =====
+
+/// An iterator over the strings in an [`MStrVec`], as string slices.
+///
+/// Created by [`MStrVec::iter`].
+#[derive(Clone)]
+pub struct Iter<'v> {
+    spans: slice::Iter<'v, Span>,
+    buf: &'v str,
+}
+
+unsafe impl Send for Iter<'_> {}
+unsafe impl Sync for Iter<'_> {}
+
+impl<'v> Iterator for Iter<'v> {
+    type Item = &'v str;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.spans.next()?;
+        // SAFETY: buf and spans come from the same MStrVec, which outlives 'v
+        Some(unsafe { span.as_str(self.buf) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let span = self.spans.next_back()?;
+        // SAFETY: buf and spans come from the same MStrVec, which outlives 'v
+        Some(unsafe { span.as_str(self.buf) })
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+impl Debug for Iter<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An owning iterator over the strings in an [`MStrVec`].
+///
+/// Borrowed strings are yielded as borrowed `MStr`s,
+/// and owned strings are copied into new owned `MStr`s.
+///
+/// Created by [`MStrVec::into_iter`].
+pub struct IntoIter<'a> {
+    spans: alloc::vec::IntoIter<Span>,
+    buf: String,
+    _marker: PhantomData<&'a str>,
+}
+
+unsafe impl Send for IntoIter<'_> {}
+unsafe impl Sync for IntoIter<'_> {}
+
+impl<'a> IntoIter<'a> {
+    fn resolve(&self, span: Span) -> MStr<'a> {
+        if span.is_owned() {
+            // SAFETY: buf and spans come from the same MStrVec
+            MStr::new_owned(unsafe { span.as_str(&self.buf) })
+        } else {
+            // SAFETY: span is borrowed, and the str lives for 'a
+            MStr::new_borrowed(unsafe { span.as_borrowed() })
+        }
+    }
+}
+
+impl<'a> Iterator for IntoIter<'a> {
+    type Item = MStr<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.spans.next()?;
+        Some(self.resolve(span))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IntoIter<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let span = self.spans.next_back()?;
+        Some(self.resolve(span))
+    }
+}
+
+impl ExactSizeIterator for IntoIter<'_> {}
+
+impl FusedIterator for IntoIter<'_> {}
+
+impl Debug for IntoIter<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let iter = Iter {
+            spans: self.spans.as_slice().iter(),
+            buf: &self.buf,
+        };
+        f.debug_tuple("IntoIter").field(&iter).finish()
+    }
+}
+
+// ===== Trait Impls =====
+
+impl<'a> IntoIterator for MStrVec<'a> {
+    type Item = MStr<'a>;
+    type IntoIter = IntoIter<'a>;
+
+    /// Converts this `MStrVec` into an iterator of `MStr`s.
+    ///
+    /// Borrowed strings are yielded as borrowed `MStr`s,
+    /// and owned strings are copied into new owned `MStr`s.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::{MStr, MStrVec};
+    /// let vec = MStrVec::from_iter([MStr::new_borrowed("a"), MStr::new_owned("b")]);
+    /// let mstrs = vec.into_iter().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(mstrs, ["a", "b"]);
+    /// assert!(mstrs[0].is_borrowed());
+    /// assert!(mstrs[1].is_owned());
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            spans: self.spans.into_iter(),
+            buf: self.buf,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'v> IntoIterator for &'v MStrVec<'_> {
+    type Item = &'v str;
+    type IntoIter = Iter<'v>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: Into<MStr<'a>>> Extend<T> for MStrVec<'a> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.spans.reserve(iter.size_hint().0);
+        iter.for_each(|s| self.push(s));
+    }
+}
+
+impl<'a, T: Into<MStr<'a>>> FromIterator<T> for MStrVec<'a> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = MStrVec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl Index<usize> for MStrVec<'_> {
+    type Output = str;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get(index) {
+            Some(s) => s,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len(),
+                index
+            ),
+        }
+    }
+}
+
+impl Debug for MStrVec<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl Eq for MStrVec<'_> {}
+
+impl PartialEq for MStrVec<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+// ===== serde =====
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::*;
+    use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error, SeqAccess, Visitor};
+    use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+    // -- Serialize --
+
+    impl Serialize for MStrVec<'_> {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            let mut seq = s.serialize_seq(Some(self.len()))?;
+            for s in self {
+                seq.serialize_element(s)?;
+            }
+            seq.end()
+        }
+    }
+
+    // -- Deserialize --
+
+    struct MStrVecVisitor;
+
+    impl<'de> Visitor<'de> for MStrVecVisitor {
+        type Value = MStrVec<'static>;
+
+        fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str("a sequence of strings")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            // cap the preallocation, the size hint comes from untrusted input
+            let mut vec = MStrVec::with_capacity(seq.size_hint().unwrap_or(0).min(4096), 0);
+            while seq.next_element_seed(PushOwned(&mut vec))?.is_some() {}
+            Ok(vec)
+        }
+    }
+
+    // deserializes a string directly into an MStrVec's buffer, without allocating it separately
+    struct PushOwned<'v, 'a>(&'v mut MStrVec<'a>);
+
+    impl<'de> DeserializeSeed<'de> for PushOwned<'_, '_> {
+        type Value = ();
+
+        fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+            d.deserialize_str(self)
+        }
+    }
+
+    impl Visitor<'_> for PushOwned<'_, '_> {
+        type Value = ();
+
+        fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str("a string")
+        }
+
+        fn visit_str<E: Error>(self, s: &str) -> Result<Self::Value, E> {
+            self.0.push_owned(s);
+            Ok(())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for MStrVec<'_> {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            d.deserialize_seq(MStrVecVisitor)
+        }
+    }
+
+    // -- Unit Tests --
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use serde_test::{assert_tokens, Token};
+
+        #[test]
+        fn basic() {
+            let mut vec = MStrVec::new();
+            vec.push("foo");
+            vec.push(String::from("bar"));
+
+            assert_tokens(
+                &vec,
+                &[
+                    Token::Seq { len: Some(2) },
+                    Token::Str("foo"),
+                    Token::Str("bar"),
+                    Token::SeqEnd,
+                ],
+            );
+        }
+
+        #[test]
+        fn always_de_owned() {
+            let json = String::from(r#"["ribbit", "croak", "\"escaped\""]"#);
+
+            let vec: MStrVec<'static> = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(
+                vec.iter().collect::<Vec<_>>(),
+                ["ribbit", "croak", "\"escaped\""]
+            );
+            assert!((0..vec.len()).all(|i| vec.is_owned(i)));
+        }
+    }
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn mixed() -> MStrVec<'static> {
+        let mut v = MStrVec::new();
+        v.push("one");
+        v.push(String::from("two"));
+        v.push("three");
+        v.push(String::from("four"));
+        v.push(String::new());
+        v
+    }
+
+    #[test]
+    fn push_get() {
+        let v = mixed();
+
+        assert_eq!(v.len(), 5);
+        assert_eq!(v.get(0), Some("one"));
+        assert_eq!(v.get(1), Some("two"));
+        assert_eq!(v.get(2), Some("three"));
+        assert_eq!(v.get(3), Some("four"));
+        assert_eq!(v.get(4), Some(""));
+        assert_eq!(v.get(5), None);
+
+        assert_eq!(&v[3], "four");
+    }
+
+    #[test]
+    fn repr() {
+        let v = mixed();
+
+        assert!(v.is_borrowed(0));
+        assert!(v.is_owned(1));
+        assert!(v.is_borrowed(2));
+        assert!(v.is_owned(3));
+        assert!(v.is_owned(4));
+
+        // only owned data lives in the buffer
+        assert_eq!(v.buf, "twofour");
+    }
+
+    #[test]
+    fn borrowed_ptr() {
+        let s = String::from("quack");
+        let mut v = MStrVec::new();
+        v.push(s.as_str());
+
+        assert_eq!(v.get(0).unwrap().as_ptr(), s.as_ptr());
+        assert_eq!(v.get_borrowed(0).unwrap().as_ptr(), s.as_ptr());
+        assert_eq!(v.get_mstr(0).unwrap().as_ptr(), s.as_ptr());
+    }
+
+    #[test]
+    fn get_borrowed_outlives_vec() {
+        let s = String::from("meow");
+        let mut v = MStrVec::new();
+        v.push(s.as_str());
+        v.push(String::from("purr"));
+
+        let b = v.get_borrowed(0);
+        assert_eq!(v.get_borrowed(1), None);
+        drop(v);
+        assert_eq!(b, Some("meow"));
+    }
+
+    #[test]
+    fn iter() {
+        let v = mixed();
+
+        assert_eq!(
+            v.iter().collect::<Vec<_>>(),
+            ["one", "two", "three", "four", ""]
+        );
+        assert_eq!(
+            v.iter().rev().collect::<Vec<_>>(),
+            ["", "four", "three", "two", "one"]
+        );
+        assert_eq!(v.iter().len(), 5);
+        assert!(v.iter_mstr().all(|s| s.is_borrowed()));
+    }
+
+    #[test]
+    fn into_iter() {
+        let mstrs = mixed().into_iter().collect::<Vec<_>>();
+
+        assert_eq!(mstrs, ["one", "two", "three", "four", ""]);
+        assert_eq!(
+            mstrs.iter().map(MStr::is_owned).collect::<Vec<_>>(),
+            [false, true, false, true, true]
+        );
+
+        let rev = mixed().into_iter().rev().collect::<Vec<_>>();
+        assert_eq!(rev, ["", "four", "three", "two", "one"]);
+    }
+
+    #[test]
+    fn pop() {
+        let mut v = mixed();
+
+        assert_eq!(v.pop(), Some(MStr::new_owned("")));
+        let four = v.pop().unwrap();
+        assert!(four.is_owned());
+        assert_eq!(four, "four");
+        assert_eq!(v.buf, "two");
+
+        let three = v.pop().unwrap();
+        assert!(three.is_borrowed());
+        assert_eq!(three, "three");
+
+        v.push(String::from("five"));
+        assert_eq!(v.iter().collect::<Vec<_>>(), ["one", "two", "five"]);
+    }
+
+    #[test]
+    fn extend_clone_eq() {
+        let mut v = MStrVec::from_iter(vec!["a", "b"]);
+        v.extend(vec![String::from("c")]);
+        v.extend([MStr::new_borrowed("d"), MStr::new_owned("e")]);
+
+        let clone = v.clone();
+        assert_eq!(v, clone);
+        assert!(clone.is_owned(2));
+        assert!(clone.is_borrowed(3));
+        assert_eq!(clone.iter().collect::<Vec<_>>(), ["a", "b", "c", "d", "e"]);
+
+        assert_ne!(v, MStrVec::from_iter(["a"]));
+    }
+
+    #[test]
+    fn clear() {
+        let mut v = mixed();
+        v.clear();
+
+        assert!(v.is_empty());
+        assert!(v.buf.is_empty());
+        assert_eq!(v.get(0), None);
+    }
+
+    #[test]
+    fn debug() {
+        assert_eq!(
+            alloc::format!("{:?}", mixed()),
+            r#"["one", "two", "three", "four", ""]"#
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_oob() {
+        let _ = &mixed()[5];
+    }
+
+    #[test]
+    fn assert_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<MStrVec<'_>>();
+        assert_send_sync::<IntoIter<'_>>();
+        assert_send_sync::<Iter<'_>>();
+    }
+}
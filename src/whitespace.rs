@@ -0,0 +1,426 @@
+use crate::MStr;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::str;
+
+// ===== MStr Methods =====
+
+impl<'a> MStr<'a> {
+    /// Converts every line ending to `\n`.
+    ///
+    /// Both `\r\n` (Windows) and lone `\r` (classic Mac) become `\n`.
+    ///
+    /// If there are no `\r`s, `self` is returned unchanged.
+    /// Otherwise owned strings are converted in place, and borrowed strings are copied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("one\r\ntwo\rthree\n");
+    /// assert_eq!(s.normalize_newlines(), "one\ntwo\nthree\n");
+    ///
+    /// assert!(MStr::new_borrowed("a\nb").normalize_newlines().is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn normalize_newlines(self) -> MStr<'a> {
+        let Some(first) = self.bytes().position(|b| b == b'\r') else {
+            return self;
+        };
+
+        self.edit_bytes(|bytes| {
+            let mut w = first;
+            let mut r = first;
+            while r < bytes.len() {
+                let b = bytes[r];
+                r += 1;
+
+                if b == b'\r' {
+                    if bytes.get(r) == Some(&b'\n') {
+                        // the \n is copied next
+                        continue;
+                    }
+                    bytes[w] = b'\n';
+                } else {
+                    bytes[w] = b;
+                }
+                w += 1;
+            }
+            bytes.truncate(w);
+        })
+    }
+
+    /// Replaces every run of whitespace with a single space.
+    ///
+    /// Whitespace is anything [`char::is_whitespace`] matches, including newlines.
+    /// Leading and trailing whitespace is collapsed too, not removed.
+    ///
+    /// If there are no runs to collapse (and no whitespace other than single spaces), `self` is returned unchanged.
+    /// Otherwise owned strings are converted in place, and borrowed strings are copied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("  lots \t of\n\nspace ");
+    /// assert_eq!(s.collapse_whitespace(), " lots of space ");
+    ///
+    /// assert!(MStr::new_borrowed("a b c").collapse_whitespace().is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn collapse_whitespace(self) -> MStr<'a> {
+        let mut prev_ws = false;
+        let needs_change = self.chars().any(|c| {
+            let ws = c.is_whitespace();
+            let change = ws && (prev_ws || c != ' ');
+            prev_ws = ws;
+            change
+        });
+        if !needs_change {
+            return self;
+        }
+
+        self.edit_bytes(|bytes| {
+            let mut w = 0;
+            let mut r = 0;
+            let mut prev_ws = false;
+            while r < bytes.len() {
+                let len = char_len(bytes[r]);
+                let ws = is_whitespace_at(bytes, r, len);
+
+                if !ws {
+                    bytes.copy_within(r..r + len, w);
+                    w += len;
+                } else if !prev_ws {
+                    bytes[w] = b' ';
+                    w += 1;
+                }
+
+                prev_ws = ws;
+                r += len;
+            }
+            bytes.truncate(w);
+        })
+    }
+
+    /// Removes a leading byte order mark (`U+FEFF`), if there is one.
+    ///
+    /// Borrowed strings stay borrowed (the BOM is sliced off),
+    /// and owned strings have the BOM removed in place.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("\u{FEFF}text").strip_bom();
+    /// assert_eq!(s, "text");
+    /// assert!(s.is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn strip_bom(self) -> MStr<'a> {
+        const BOM: &str = "\u{FEFF}";
+
+        if !self.starts_with(BOM) {
+            return self;
+        }
+        if let Some(s) = self.as_borrowed() {
+            return MStr::new_borrowed(&s[BOM.len()..]);
+        }
+
+        let mut s = self.into_string();
+        s.drain(..BOM.len());
+        MStr::new_owned(s)
+    }
+
+    /// Removes the leading whitespace that every line has in common.
+    ///
+    /// The common indentation is the longest run of spaces and tabs that every non-blank line starts with.
+    /// Spaces and tabs are not treated as equivalent.
+    /// Blank lines (empty or whitespace only) don't count, and lose up to that much indentation too.
+    ///
+    /// If there is no common indentation, `self` is returned unchanged.
+    /// Otherwise owned strings are converted in place, and borrowed strings are copied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let code = MStr::new_borrowed("
+    ///     fn main() {
+    ///         println!(\"hi\");
+    ///     }
+    /// ");
+    /// assert_eq!(code.dedent(), "\nfn main() {\n    println!(\"hi\");\n}\n");
+    ///
+    /// assert!(MStr::new_borrowed("a\n  b").dedent().is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn dedent(self) -> MStr<'a> {
+        let margin = common_indent(&self);
+        if margin.is_empty() {
+            return self;
+        }
+        let margin = margin.len();
+
+        self.edit_bytes(|bytes| {
+            let mut w = 0;
+            let mut r = 0;
+            while r < bytes.len() {
+                // at the start of a line
+                let indent = bytes[r..]
+                    .iter()
+                    .take(margin)
+                    .take_while(|&&b| b == b' ' || b == b'\t')
+                    .count();
+                r += indent;
+
+                let end = bytes[r..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(bytes.len(), |n| r + n + 1);
+                bytes.copy_within(r..end, w);
+                w += end - r;
+                r = end;
+            }
+            bytes.truncate(w);
+        })
+    }
+
+    /// Replaces every tab with spaces, up to the next multiple of `tab_width` columns.
+    ///
+    /// Columns are counted in `char`s from the start of the line.
+    /// A `tab_width` of 0 removes tabs.
+    ///
+    /// If there are no tabs, `self` is returned unchanged.
+    /// Otherwise a new string is allocated (the result is longer, so it can't be converted in place).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("a\tbc\td\n\te");
+    /// assert_eq!(s.expand_tabs(4), "a   bc  d\n    e");
+    ///
+    /// assert!(MStr::new_borrowed("no tabs").expand_tabs(4).is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn expand_tabs(self, tab_width: usize) -> MStr<'a> {
+        let Some(first) = self.find('\t') else {
+            return self;
+        };
+
+        let mut out = String::with_capacity(self.len() + tab_width * 4);
+        out.push_str(&self[..first]);
+        // the column of `first`
+        let mut col = self[..first]
+            .rsplit('\n')
+            .next()
+            .map_or(0, |line| line.chars().count());
+
+        for c in self[first..].chars() {
+            match c {
+                '\t' if tab_width == 0 => {}
+                '\t' => {
+                    let spaces = tab_width - col % tab_width;
+                    out.extend(core::iter::repeat(' ').take(spaces));
+                    col += spaces;
+                }
+                '\n' => {
+                    out.push('\n');
+                    col = 0;
+                }
+                c => {
+                    out.push(c);
+                    col += 1;
+                }
+            }
+        }
+
+        MStr::new_owned(out)
+    }
+
+    // edits the bytes of this string with `edit`, which must keep them valid UTF-8
+    // owned strings are edited in their own buffer, and borrowed strings are copied first
+    fn edit_bytes(self, edit: impl FnOnce(&mut Vec<u8>)) -> MStr<'a> {
+        let mut bytes = self.into_string().into_bytes();
+        edit(&mut bytes);
+
+        debug_assert!(str::from_utf8(&bytes).is_ok());
+        // SAFETY: guaranteed by `edit`
+        MStr::new_owned(unsafe { String::from_utf8_unchecked(bytes) })
+    }
+}
+
+// the indentation that every non-blank line of `s` starts with
+fn common_indent(s: &str) -> &str {
+    let mut common: Option<&str> = None;
+
+    for line in s.split('\n') {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+        let indent = &line[..indent_len];
+
+        common = Some(match common {
+            None => indent,
+            Some(common) => {
+                // the indentation is ascii, so any byte index is a char boundary
+                let same = common
+                    .bytes()
+                    .zip(indent.bytes())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                &common[..same]
+            }
+        });
+
+        if common == Some("") {
+            break;
+        }
+    }
+
+    common.unwrap_or("")
+}
+
+// the length of the UTF-8 sequence that starts with `b`
+#[inline]
+fn char_len(b: u8) -> usize {
+    match b {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        _ => 4,
+    }
+}
+
+// whether the `len`-byte char at `bytes[i..]` is whitespace
+#[inline]
+fn is_whitespace_at(bytes: &[u8], i: usize, len: usize) -> bool {
+    if len == 1 {
+        return char::from(bytes[i]).is_whitespace();
+    }
+    str::from_utf8(&bytes[i..i + len])
+        .ok()
+        .and_then(|s| s.chars().next())
+        .map_or(false, char::is_whitespace)
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unchanged() {
+        let methods: [fn(MStr<'_>) -> MStr<'_>; 5] = [
+            |s| s.normalize_newlines(),
+            |s| s.collapse_whitespace(),
+            |s| s.strip_bom(),
+            |s| s.dedent(),
+            |s| s.expand_tabs(4),
+        ];
+
+        for s in ["", "plain", "one space", "🐸 ünïcödé"] {
+            for method in methods {
+                assert!(method(MStr::new_borrowed(s)).is_borrowed());
+
+                let owned = MStr::new_owned(s);
+                let ptr = owned.as_ptr();
+                let out = method(owned);
+                assert!(out.is_owned());
+                assert_eq!(out.as_ptr(), ptr);
+                assert_eq!(out, s);
+            }
+        }
+    }
+
+    #[test]
+    fn normalize_newlines() {
+        let cases = [
+            ("a\r\nb", "a\nb"),
+            ("a\rb", "a\nb"),
+            ("\r\r\n\n\r", "\n\n\n\n"),
+            ("🐸\r\n🐸\r", "🐸\n🐸\n"),
+            ("\r\n", "\n"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(MStr::new_borrowed(input).normalize_newlines(), expected);
+            assert_eq!(MStr::new_owned(input).normalize_newlines(), expected);
+        }
+    }
+
+    #[test]
+    fn collapse_whitespace() {
+        let cases = [
+            ("a  b", "a b"),
+            ("a\tb", "a b"),
+            ("  a  ", " a "),
+            ("a \t\r\n b", "a b"),
+            ("\n", " "),
+            // non-ascii whitespace shrinks to a single byte
+            ("a\u{3000}b\u{A0}\u{2028} c", "a b c"),
+            ("🐸  🐸", "🐸 🐸"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(MStr::new_borrowed(input).collapse_whitespace(), expected);
+            assert_eq!(MStr::new_owned(input).collapse_whitespace(), expected);
+        }
+    }
+
+    #[test]
+    fn strip_bom() {
+        let input = "\u{FEFF}\u{FEFF}text";
+        let s = MStr::new_borrowed(input).strip_bom();
+        assert_eq!(s, "\u{FEFF}text");
+        assert!(s.is_borrowed());
+        assert_eq!(s.as_ptr(), input[3..].as_ptr());
+
+        let s = MStr::new_owned(input).strip_bom();
+        assert_eq!(s, "\u{FEFF}text");
+        assert!(s.is_owned());
+
+        // only at the start
+        assert!(MStr::new_borrowed("a\u{FEFF}").strip_bom().is_borrowed());
+    }
+
+    #[test]
+    fn dedent() {
+        let cases = [
+            ("  a\n  b", "a\nb"),
+            ("    a\n  b\n      c", "  a\nb\n    c"),
+            // blank lines don't count
+            ("  a\n\n \n  b\n", "a\n\n\nb\n"),
+            ("\n    a\n      \n    b\n  ", "\na\n  \nb\n"),
+            // tabs and spaces are different
+            ("\t a\n\t b", "a\nb"),
+            ("\t a\n \tb", "\t a\n \tb"),
+            ("  🐸\r\n  🐸", "🐸\r\n🐸"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(MStr::new_borrowed(input).dedent(), expected, "{input:?}");
+            assert_eq!(MStr::new_owned(input).dedent(), expected, "{input:?}");
+        }
+
+        assert!(MStr::new_borrowed("a\n  b").dedent().is_borrowed());
+        assert!(MStr::new_borrowed("  \n\t\n").dedent().is_borrowed());
+    }
+
+    #[test]
+    fn expand_tabs() {
+        let s = MStr::new_borrowed("\tab\tc\n12345678\tx");
+        assert_eq!(s.clone().expand_tabs(4), "    ab  c\n12345678    x");
+        assert_eq!(
+            s.clone().expand_tabs(8),
+            "        ab      c\n12345678        x"
+        );
+        assert_eq!(s.clone().expand_tabs(1), " ab c\n12345678 x");
+        assert_eq!(s.expand_tabs(0), "abc\n12345678x");
+
+        // columns count chars, not bytes
+        assert_eq!(MStr::new_borrowed("🐸\t|").expand_tabs(4), "🐸   |");
+        assert_eq!(MStr::new_borrowed("é\n🐸\t|").expand_tabs(4), "é\n🐸   |");
+    }
+}
@@ -0,0 +1,275 @@
+//! Display width (terminal columns) aware truncation and padding.
+//!
+//! Widths come from [unicode-width](https://docs.rs/unicode-width)
+//! (East Asian wide characters and most emoji take two columns, combining marks take none),
+//! and strings are only ever cut between grapheme clusters (using [unicode-segmentation](https://docs.rs/unicode-segmentation)),
+//! so an accent is never separated from its letter, and a flag or emoji sequence is never split.
+//!
+//! Requires the `width` feature.
+
+use crate::MStr;
+use core::iter;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+const ELLIPSIS: char = '…';
+
+// ===== MStr Methods =====
+
+impl<'a> MStr<'a> {
+    /// Returns the number of columns this string takes up when displayed in a terminal.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// assert_eq!(MStr::new_borrowed("abc").display_width(), 3);
+    /// assert_eq!(MStr::new_borrowed("日本語").display_width(), 6);
+    /// assert_eq!(MStr::new_borrowed("cafe\u{301}").display_width(), 4);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn display_width(&self) -> usize {
+        if is_printable_ascii(self) {
+            return self.len();
+        }
+        UnicodeWidthStr::width(self.as_str())
+    }
+
+    /// Cuts this string down to at most `max_width` columns.
+    ///
+    /// The string is cut between grapheme clusters, so the result may be a column short
+    /// if a wide character would straddle the limit.
+    ///
+    /// If the string already fits, `self` is returned unchanged.
+    /// Otherwise borrowed strings stay borrowed (the end is sliced off),
+    /// and owned strings are truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// let s = MStr::new_borrowed("日本語").truncate_to_width(5);
+    /// assert_eq!(s, "日本");
+    /// assert!(s.is_borrowed());
+    ///
+    /// assert_eq!(MStr::new_borrowed("short").truncate_to_width(10), "short");
+    /// ```
+    #[must_use]
+    pub fn truncate_to_width(self, max_width: usize) -> MStr<'a> {
+        let end = fit(&self, max_width);
+        self.truncated(end)
+    }
+
+    /// Cuts this string down to at most `max_width` columns, ending with an ellipsis (`…`) if anything was cut.
+    ///
+    /// The ellipsis takes up one column, so the rest of the string is cut to `max_width - 1` columns
+    /// (between grapheme clusters, like [`truncate_to_width`](MStr::truncate_to_width)).
+    /// If `max_width` is `0`, the result is empty.
+    ///
+    /// If the string already fits, `self` is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// assert_eq!(MStr::new_borrowed("a long message").ellipsize(8), "a long …");
+    /// assert_eq!(MStr::new_borrowed("日本語").ellipsize(5), "日本…");
+    ///
+    /// let s = MStr::new_borrowed("fits").ellipsize(4);
+    /// assert_eq!(s, "fits");
+    /// assert!(s.is_borrowed());
+    /// ```
+    #[must_use]
+    pub fn ellipsize(self, max_width: usize) -> MStr<'a> {
+        if fit(&self, max_width) == self.len() {
+            return self;
+        }
+        if max_width == 0 {
+            return MStr::new_borrowed("");
+        }
+
+        let end = fit(&self, max_width - 1);
+        let mut s = self.into_string();
+        s.truncate(end);
+        s.push(ELLIPSIS);
+        MStr::new_owned(s)
+    }
+
+    /// Pads the end of this string with spaces, so that it is at least `width` columns wide.
+    ///
+    /// If the string is already at least that wide, `self` is returned unchanged.
+    /// Combine with [`truncate_to_width`](MStr::truncate_to_width) (or [`ellipsize`](MStr::ellipsize))
+    /// to get a string that is exactly `width` columns wide.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mstr::MStr;
+    /// assert_eq!(MStr::new_borrowed("日本").pad_to_width(6), "日本  ");
+    ///
+    /// let s = MStr::new_borrowed("a long message").ellipsize(8).pad_to_width(8);
+    /// assert_eq!(s, "a long …");
+    /// assert_eq!(s.display_width(), 8);
+    /// ```
+    #[must_use]
+    pub fn pad_to_width(self, width: usize) -> MStr<'a> {
+        let current = self.display_width();
+        if current >= width {
+            return self;
+        }
+
+        let mut s = self.into_string();
+        s.extend(iter::repeat(' ').take(width - current));
+        MStr::new_owned(s)
+    }
+
+    // the first `end` bytes of this string
+    fn truncated(self, end: usize) -> MStr<'a> {
+        if end == self.len() {
+            return self;
+        }
+        if let Some(s) = self.as_borrowed() {
+            return MStr::new_borrowed(&s[..end]);
+        }
+
+        let mut s = self.into_string();
+        s.truncate(end);
+        MStr::new_owned(s)
+    }
+}
+
+// the length in bytes of the longest prefix of `s` (ending between grapheme clusters) that is at most `max_width` columns
+fn fit(s: &str, max_width: usize) -> usize {
+    // every printable ascii char is its own grapheme, and one column wide
+    if is_printable_ascii(s) {
+        return s.len().min(max_width);
+    }
+
+    let mut width = 0;
+    for (i, g) in s.grapheme_indices(true) {
+        width += UnicodeWidthStr::width(g);
+        if width > max_width {
+            return i;
+        }
+    }
+    s.len()
+}
+
+#[inline]
+fn is_printable_ascii(s: &str) -> bool {
+    s.bytes().all(|b| matches!(b, b' '..=b'~'))
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_width() {
+        for (s, width) in [
+            ("", 0),
+            ("ascii", 5),
+            ("日本語", 6),
+            ("ｈａｌｆ", 8),
+            ("e\u{301}", 1),
+            ("\u{200B}", 0),
+            ("🐸", 2),
+            ("👨\u{200D}👩\u{200D}👧", 2),
+            ("🇯🇵", 2),
+        ] {
+            assert_eq!(MStr::new_borrowed(s).display_width(), width, "{s:?}");
+        }
+    }
+
+    #[test]
+    fn truncate() {
+        for (s, max, expected) in [
+            ("ascii", 3, "asc"),
+            ("ascii", 0, ""),
+            ("日本語", 4, "日本"),
+            ("日本語", 3, "日"),
+            ("a日", 2, "a"),
+            // combining marks stay with their letter
+            ("cafe\u{301}s", 4, "cafe\u{301}"),
+            ("e\u{301}\u{301}x", 1, "e\u{301}\u{301}"),
+            // sequences aren't split
+            ("👨\u{200D}👩\u{200D}👧!", 2, "👨\u{200D}👩\u{200D}👧"),
+            ("🇯🇵🇫🇷", 3, "🇯🇵"),
+            ("\r\nx", 0, "\r\n"),
+        ] {
+            let t = MStr::new_borrowed(s).truncate_to_width(max);
+            assert_eq!(t, expected, "{s:?} to {max}");
+            assert!(t.is_borrowed());
+            assert!(t.display_width() <= max);
+
+            let t = MStr::new_owned(s).truncate_to_width(max);
+            assert_eq!(t, expected, "{s:?} to {max}");
+            assert!(t.is_owned());
+        }
+    }
+
+    #[test]
+    fn fits_unchanged() {
+        for s in ["", "ascii", "日本語", "cafe\u{301}"] {
+            let width = MStr::new_borrowed(s).display_width();
+            for max in [width, width + 1, usize::MAX] {
+                let t = MStr::new_borrowed(s).truncate_to_width(max);
+                assert_eq!(t.as_ptr(), s.as_ptr());
+                assert!(t.is_borrowed());
+
+                let e = MStr::new_borrowed(s).ellipsize(max);
+                assert_eq!(e.as_ptr(), s.as_ptr());
+                assert!(e.is_borrowed());
+            }
+
+            let owned = MStr::new_owned(s);
+            let ptr = owned.as_ptr();
+            let owned = owned.truncate_to_width(width).ellipsize(width);
+            assert_eq!(owned.as_ptr(), ptr);
+            assert!(owned.is_owned());
+        }
+    }
+
+    #[test]
+    fn ellipsize() {
+        for (s, max, expected) in [
+            ("ascii", 4, "asc…"),
+            ("ascii", 1, "…"),
+            ("ascii", 0, ""),
+            ("日本語", 5, "日本…"),
+            ("日本語", 4, "日…"),
+            ("日本語", 2, "…"),
+            ("cafe\u{301}s", 4, "caf…"),
+        ] {
+            let e = MStr::new_borrowed(s).ellipsize(max);
+            assert_eq!(e, expected, "{s:?} to {max}");
+            assert!(e.display_width() <= max);
+        }
+    }
+
+    #[test]
+    fn pad() {
+        assert_eq!(MStr::new_borrowed("ab").pad_to_width(4), "ab  ");
+        assert_eq!(MStr::new_borrowed("日").pad_to_width(3), "日 ");
+        assert_eq!(MStr::new_borrowed("e\u{301}").pad_to_width(2), "e\u{301} ");
+        assert_eq!(MStr::new_borrowed("").pad_to_width(2), "  ");
+
+        let s = MStr::new_borrowed("日本");
+        for width in [0, 3, 4] {
+            let p = s.clone().pad_to_width(width);
+            assert!(p.is_borrowed());
+            assert_eq!(p, "日本");
+        }
+
+        // truncating then padding always gives the exact width
+        for max in 0..8 {
+            let s = MStr::new_borrowed("日本語ab")
+                .truncate_to_width(max)
+                .pad_to_width(max);
+            assert_eq!(s.display_width(), max);
+        }
+    }
+}